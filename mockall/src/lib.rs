@@ -28,11 +28,19 @@
 //!
 //! * [`Getting started`](#getting-started)
 //! * [`Static Return values`](#static-return-values)
+//! * [`Scripted return values`](#scripted-return-values)
 //! * [`Matching arguments`](#matching-arguments)
 //! * [`Call counts`](#call-counts)
 //! * [`Sequences`](#sequences)
 //! * [`Checkpoints`](#checkpoints)
+//! * [`Verifying without panicking`](#verifying-without-panicking)
+//! * [`Failures on other threads`](#failures-on-other-threads)
+//! * [`Call history`](#call-history)
+//! * [`Argument captors`](#argument-captors)
+//! * [`Nice mocks`](#nice-mocks)
+//! * [`Spies`](#spies)
 //! * [`Reference arguments`](#reference-arguments)
+//! * [`Callback arguments`](#callback-arguments)
 //! * [`Reference return values`](#reference-return-values)
 //! * [`impl Trait`](#impl-trait)
 //! * [`Mocking structs`](#mocking-structs)
@@ -73,11 +81,11 @@
 //!
 //! ## Static Return values
 //!
-//! Every expectation must have an associated return value (though when the
-//! **nightly** feature is enabled expectations will automatically return the
-//! default values of their return types, if their return types implement
-//! `Default`.).  For methods that return a `static` value, the macros will
-//! generate an `Expectation` struct like
+//! Every expectation has an associated return value.  If none is set, an
+//! expectation will return the default value of its return type, if that type
+//! implements `Default`.  For generic return types that requires the
+//! **nightly** feature.  For methods that return a `static` value, the macros
+//! will generate an `Expectation` struct like
 //! [`this`](examples::__mock_MockFoo_Foo::__foo::Expectation).
 //! There are two ways to set such an expectation's return value: with a
 //! constant
@@ -178,6 +186,37 @@
 //! # }
 //! ```
 //!
+//! ## Scripted return values
+//!
+//! To return a fixed series of values, one per call, use `return_sequence`.
+//! The values need not be `Clone`.  `returning_iter` does the same with any
+//! iterator, advancing it only as the method is called.  Methods that return
+//! by reference support `return_sequence` too.
+//!
+//! What happens after the last value is set by `when_exhausted`.  By default,
+//! the next call panics.  [`Exhausted::RepeatLast`] keeps returning the last
+//! value, which must be `Clone` unless the method returns by reference.
+//! [`Exhausted::FallThrough`] lets later expectations handle the remaining
+//! calls.
+//!
+//! ```
+//! # use mockall::*;
+//! #[automock]
+//! trait Foo {
+//!     fn foo(&self) -> Result<u32, String>;
+//! }
+//!
+//! let mut mock = MockFoo::new();
+//! mock.expect_foo()
+//!     .return_sequence(vec![Err("busy".to_owned()), Ok(1)])
+//!     .when_exhausted(Exhausted::FallThrough);
+//! mock.expect_foo()
+//!     .returning(|| Ok(0));
+//! assert!(mock.foo().is_err());
+//! assert_eq!(Ok(1), mock.foo());
+//! assert_eq!(Ok(0), mock.foo());
+//! ```
+//!
 //! ## Matching arguments
 //!
 //! Optionally, expectations may have argument matchers set.  A matcher will
@@ -201,6 +240,15 @@
 //! mock.foo(0);    // Panics!
 //! ```
 //!
+//! When no expectation matches a call, the panic message lists every current
+//! expectation for that method along with the reason it was rejected: which
+//! argument failed its predicate and why, or that the expectation had already
+//! been called as many times as allowed.  Each expectation is identified by
+//! its matcher and by the place where it was created.  Matchers set with
+//! `withf` can't describe themselves, so such expectations may be given a
+//! human-readable name with
+//! [`named`](examples::__mock_MockFoo_Foo::__foo::Expectation::named).
+//!
 //! See [`predicate`] for a list of Mockall's builtin predicate functions.
 //! For convenience,
 //! [`withf`](examples::__mock_MockFoo_Foo::__foo::Expectation::withf)
@@ -332,6 +380,47 @@
 //! # }
 //! ```
 //!
+//! Expectations in a sequence may use a range of call counts.  The sequence
+//! may advance to the next expectation as soon as the current one has been
+//! called its minimum number of times.
+//!
+//! An expectation may also belong to several sequences at once.  That's useful
+//! for independent orderings that share a step, like "open before read" and
+//! "open before write", where read and write are unordered relative to each
+//! other.
+//!
+//! A sequence can also contain unordered groups of expectations, delimited by
+//! [`Sequence::begin_group`] and [`Sequence::end_group`].  The members of a
+//! group may be called in any order, but all of them must be satisfied before
+//! the sequence moves past the group.
+//!
+//! When only a partial order is needed, an expectation can instead be made a
+//! [`Prerequisite`] of others.  An expectation that is `after` one or more
+//! prerequisites may not be called until all of them have been satisfied.
+//!
+//! ```should_panic(expected = "Prerequisite violation")
+//! # use mockall::*;
+//! #[automock]
+//! trait Foo {
+//!     fn write(&self, x: u32);
+//!     fn commit(&self);
+//! }
+//!
+//! # fn main() {
+//! let mut mock = MockFoo::new();
+//! let write = mock.expect_write()
+//!     .times(2)
+//!     .returning(|_| ())
+//!     .prerequisite();
+//! mock.expect_commit()
+//!     .returning(|| ())
+//!     .after(&[&write]);
+//!
+//! mock.write(1);
+//! mock.commit();  // Panics!  write should've been called twice first.
+//! # }
+//! ```
+//!
 //! ## Checkpoints
 //!
 //! Sometimes its useful to validate all expectations mid-test, throw them away,
@@ -376,6 +465,218 @@
 //! # }
 //! ```
 //!
+//! ## Verifying without panicking
+//!
+//! Every mock object also has a `verify` method, and every static method's
+//! `Context` object has one too.  Like `checkpoint`, it checks whether each
+//! expectation has been called an allowed number of times.  But instead of
+//! panicking it returns an [`Error`] for each one that hasn't, and it doesn't
+//! clear anything.  Each `Error` carries the method name, the expectation's
+//! description, the expected range of call counts, the actual count, and the
+//! arguments of the calls that the expectation handled.  It also returns an
+//! `Error` for each earlier call that panicked because it matched no
+//! expectation, or violated a [`Sequence`] or a prerequisite; its
+//! [`kind`](Error::kind) tells which.  That's useful for custom test harnesses
+//! and property-testing frameworks.
+//!
+//! ```
+//! # use mockall::*;
+//! #[automock]
+//! trait Foo {
+//!     fn foo(&self, x: u32);
+//! }
+//!
+//! let mut mock = MockFoo::new();
+//! mock.expect_foo()
+//!     .times(2)
+//!     .return_const(());
+//!
+//! mock.foo(1);
+//! let errors = mock.verify().unwrap_err();
+//! assert_eq!("MockFoo::foo", errors[0].method());
+//! assert_eq!(2..3, errors[0].expected());
+//! assert_eq!(1, errors[0].count());
+//! mock.foo(2);
+//! assert!(mock.verify().is_ok());
+//! ```
+//!
+//! ## Failures on other threads
+//!
+//! A mock method panics whenever a call violates its expectations.  But if
+//! the call came from a spawned thread or an async executor's worker, that
+//! panic may be swallowed, and the test could pass anyway.  So Mockall also
+//! records each violation on the mock object, and panics again with it when
+//! the mock gets checkpointed or dropped on a different thread.  For static
+//! methods, that happens when the `Context` gets checkpointed or dropped.
+//!
+//! ```should_panic
+//! # use mockall::*;
+//! # use std::{sync::Arc, thread};
+//! #[automock]
+//! trait Foo {
+//!     fn foo(&self, x: u32);
+//! }
+//!
+//! let mock = Arc::new(MockFoo::new());
+//! let mock2 = mock.clone();
+//! // The spawned thread panics, but its panic is ignored
+//! let _ = thread::spawn(move || mock2.foo(5)).join();
+//! drop(mock);     // Panics!  foo has no expectations.
+//! ```
+//!
+//! Alternatively, [`set_failure_handler`] installs a function that will be
+//! called with the message of every violation, on the thread that raised it.
+//! Custom test harnesses can use it to collect failures themselves.  While a
+//! handler is installed, violations are not recorded on the mock objects.
+//! The handler is global, so it also sees the violations of any other tests
+//! that run concurrently.
+//!
+//! ## Call history
+//!
+//! Every mock method records each call made to it, whether or not any
+//! expectation matched.  The history can be retrieved with the method's
+//! `calls_*` method, or with `Context::calls` for static methods.  Each
+//! [`Call`] records the `Debug` representation of its arguments (or just their
+//! type names, for arguments that don't implement `Debug`), which expectation
+//! handled it, and a global index that can be used to compare the order of
+//! calls to different methods.  This is useful for tests that first exercise
+//! the code under test and then make assertions.  Checkpoints clear the call
+//! history along with the expectations.
+//!
+//! ```
+//! # use mockall::*;
+//! #[automock]
+//! trait Foo {
+//!     fn foo(&self, x: u32);
+//!     fn bar(&self);
+//! }
+//!
+//! let mut mock = MockFoo::new();
+//! mock.expect_foo().return_const(());
+//! mock.expect_bar().return_const(());
+//! mock.foo(1);
+//! mock.bar();
+//! mock.foo(2);
+//!
+//! let foo_calls = mock.calls_foo();
+//! assert_eq!(2, foo_calls.len());
+//! assert_eq!(Some(0), foo_calls[0].expectation());
+//! assert!(foo_calls[0].index() < mock.calls_bar()[0].index());
+//! ```
+//!
+//! ## Argument captors
+//!
+//! The call history only records how arguments print.  To make assertions
+//! about the arguments themselves, attach a [`Captor`] to an expectation with
+//! its `capture` method.  Every call that the expectation handles will store
+//! an owned copy of the selected argument, numbered from zero.  Calls that
+//! the expectation doesn't handle, for example because its matcher rejected
+//! them, are not captured.  A reference argument is stored in its owned form,
+//! for example `&str` as `String`.
+//!
+//! The captor's type must be given explicitly.  Only arguments whose types
+//! implement `ToOwned` and contain no lifetimes or generic parameters can be
+//! captured; `capture` panics for other arguments.
+//!
+//! ```
+//! # use mockall::*;
+//! #[automock]
+//! trait Foo {
+//!     fn foo(&self, id: u32, name: &str);
+//! }
+//!
+//! let names = Captor::<String>::new();
+//! let mut mock = MockFoo::new();
+//! mock.expect_foo()
+//!     .capture(1, &names)
+//!     .return_const(());
+//! mock.foo(1, "alice");
+//! mock.foo(2, "bob");
+//! assert_eq!(vec!["alice", "bob"], names.values());
+//! ```
+//!
+//! ## Nice mocks
+//!
+//! Normally a call that matches no expectation panics.  But for large traits,
+//! a test may only care about a few methods.  A mock created with `nice`
+//! instead handles such calls with each method's *fallback*, which by default
+//! returns a default value.  Like any other [`Call`], they are recorded in the
+//! call history.  The fallback is a regular expectation that can be customized
+//! with the method's `fallback_*` method.  Even the methods of a mock created
+//! with `new` may be given fallbacks.
+//!
+//! ```
+//! # use mockall::*;
+//! #[automock]
+//! trait Foo {
+//!     fn foo(&self, x: u32) -> u32;
+//!     fn bar(&self, x: u32);
+//! }
+//!
+//! let mut mock = MockFoo::nice();
+//! mock.expect_foo()
+//!     .with(predicate::eq(1))
+//!     .return_const(10u32);
+//! mock.fallback_foo()
+//!     .returning(|x| x + 1);
+//! mock.fallback_bar()
+//!     .return_const(());
+//!
+//! assert_eq!(10, mock.foo(1));
+//! assert_eq!(3, mock.foo(2));
+//! mock.bar(5);
+//! ```
+//!
+//! Like the expectations, checkpoints discard custom fallbacks, though a nice
+//! mock stays nice.  Without the `nightly` feature, methods with generic
+//! return types need a fallback before they may be called.  Nice mocks don't
+//! apply to static methods, and for generic methods they only apply to generic
+//! parameters that have an expectation or a fallback.
+//!
+//! ## Spies
+//!
+//! Sometimes a test needs a real object with just one or two of its methods
+//! overridden.  A mock created with `spy` wraps a real object that implements
+//! the mock's traits, and uses it as every trait method's fallback.  So calls
+//! that match no expectation are forwarded to the real object.  Expectations
+//! still take precedence, and work just as they do for any other mock.
+//!
+//! ```
+//! # use mockall::*;
+//! #[automock]
+//! trait Foo {
+//!     fn foo(&self, x: u32) -> u32;
+//! }
+//!
+//! struct Real;
+//! impl Foo for Real {
+//!     fn foo(&self, x: u32) -> u32 {
+//!         x * 2
+//!     }
+//! }
+//!
+//! let mut mock = MockFoo::spy(Real);
+//! mock.expect_foo()
+//!     .with(predicate::eq(1))
+//!     .times(1)
+//!     .return_const(100u32);
+//!
+//! assert_eq!(100, mock.foo(1));
+//! assert_eq!(4, mock.foo(2));
+//! ```
+//!
+//! The real object must be `Send` and `'static`.  For a struct mocked with
+//! [`mock!`], it must implement all of the struct's traits, and only those
+//! traits' methods are forwarded.  Static methods aren't forwarded, and
+//! behave as they would for a mock created with `new`.  Some other methods
+//! can't be forwarded, because Mockall changes their signatures: those that
+//! are generic, have a `where` clause, are `async` or `unsafe`, return
+//! references or other types with lifetimes, or involve `Self`, `impl Trait`,
+//! or trait objects.  A mock whose traits have any such method gets no `spy`
+//! constructor at all, and its documentation lists the methods to blame.
+//! Checkpoints discard custom fallbacks, but a spy keeps forwarding calls to
+//! its real object.
+//!
 //! ## Reference arguments
 //!
 //! Mockall can mock methods with reference arguments, too.  There's one catch:
@@ -398,6 +699,61 @@
 //! assert_eq!(6, mock.foo(&5));
 //! ```
 //!
+//! Methods often return information through `&mut` arguments.  An expectation
+//! can write through them with `set_arg_mut`, which assigns a value, or
+//! `copy_into_arg`, which copies values into the start of a slice.  Arguments
+//! are numbered from zero.  The writes happen before the return value is
+//! computed, so they combine with any of the `return*` methods.
+//!
+//! ```
+//! # use mockall::*;
+//! #[automock]
+//! trait Reader {
+//!     fn read(&mut self, buf: &mut [u8]) -> usize;
+//! }
+//!
+//! let mut mock = MockReader::new();
+//! mock.expect_read()
+//!     .copy_into_arg(0, b"abc")
+//!     .return_const(3usize);
+//! let mut buf = [0u8; 8];
+//! assert_eq!(3, mock.read(&mut buf));
+//! assert_eq!(b"abc", &buf[..3]);
+//! ```
+//!
+//! ## Callback arguments
+//!
+//! A method's closure arguments, whether generic like `F: Fn(u32)` or boxed
+//! like `Box<dyn FnOnce(u32)>`, are passed to the expectation as `Box<dyn
+//! Fn*>`.  An expectation can call one with `invoke_arg`, given the argument's
+//! number, counting from zero, and a tuple of arguments for the callback.  The
+//! callback's argument types must be `'static`.
+//!
+//! To test an API that calls a completion handler at some later time, use
+//! `invoke_arg_later` instead.  It stores each invocation in a [`Trigger`],
+//! which runs them when it fires.
+//!
+//! ```
+//! # use mockall::*;
+//! # use std::sync::{Arc, Mutex};
+//! #[automock]
+//! trait Fetcher {
+//!     fn fetch<F: FnOnce(Result<u32, String>) + 'static>(&self, done: F);
+//! }
+//!
+//! let trigger = Trigger::new();
+//! let mut mock = MockFetcher::new();
+//! mock.expect_fetch()
+//!     .invoke_arg_later(0, (Ok::<u32, String>(42),), &trigger)
+//!     .return_const(());
+//! let result = Arc::new(Mutex::new(None));
+//! let result2 = result.clone();
+//! mock.fetch(move |r| *result2.lock().unwrap() = Some(r));
+//! assert!(result.lock().unwrap().is_none());
+//! trigger.fire();
+//! assert_eq!(Some(Ok(42)), *result.lock().unwrap());
+//! ```
+//!
 //! ## Reference return values
 //!
 //! Mockall can also use reference return values.  There is one restriction: the
@@ -433,7 +789,11 @@
 //! [this](examples::__mock_MockFoo_Foo::__bar::Expectation),
 //! which
 //! gets its return value from the
-//! [`return_const`](examples::__mock_MockFoo_Foo::__bar::Expectation::return_const) method.
+//! [`return_const`](examples::__mock_MockFoo_Foo::__bar::Expectation::return_const)
+//! or
+//! [`returning`](examples::__mock_MockFoo_Foo::__bar::Expectation::returning)
+//! methods.  The expectation keeps every value that a `returning` closure
+//! creates, so references returned by earlier calls stay valid.
 //!
 //! ```
 //! # use mockall::*;
@@ -451,6 +811,12 @@
 //!     .return_const(thing);
 //!
 //! assert_eq!(42, mock.get(0).0);
+//!
+//! let mut mock = MockContainer::new();
+//! mock.expect_get()
+//!     .returning(|i| Thing(i * 2));
+//!
+//! assert_eq!(4, mock.get(2).0);
 //! # }
 //! ```
 //!
@@ -483,6 +849,33 @@
 //! # }
 //! ```
 //!
+//! To return a different value for each key,
+//! [`return_var_keyed`](examples::__mock_MockFoo_Foo::__baz::Expectation::return_var_keyed)
+//! takes a closure that computes the key from the method's arguments, and
+//! another that creates each key's value on first use.  The mock owns the
+//! values, and its `var_*` methods expose them afterwards, so the test can
+//! check what the code under test wrote through the references.
+//!
+//! ```
+//! # use mockall::*;
+//! #[automock]
+//! trait Counters {
+//!     fn entry(&mut self, name: &str) -> &mut u32;
+//! }
+//!
+//! # fn main() {
+//! let mut mock = MockCounters::new();
+//! mock.expect_entry()
+//!     .return_var_keyed(|name| name.to_owned(), |_| 0);
+//!
+//! *mock.entry("apples") += 2;
+//! *mock.entry("pears") += 1;
+//! *mock.entry("apples") += 3;
+//! assert_eq!(Some(&5), mock.var_entry(&String::from("apples")));
+//! assert_eq!(Some(&1), mock.var_entry(&String::from("pears")));
+//! # }
+//! ```
+//!
 //! Unsized types that are common targets for
 //! [`Deref`](core::ops::Deref)
 //! are special.  Mockall
@@ -704,9 +1097,9 @@
 //! lifetimes.  Mocking such a method is similar to mocking a non-generic
 //! method, with a few additional restrictions.  One restriction is that you
 //! can't match calls with `with`, you must use `withf` instead.  Another is
-//! that the generic lifetime may not appear as part of the return type.
-//! Finally, no method may have both generic lifetime parameters *and* generic
-//! type parameters.
+//! that the generic lifetime may not appear as part of the return type, except
+//! as described below.  Finally, no method may have both generic lifetime
+//! parameters *and* generic type parameters.
 //!
 //! ```
 //! # use mockall::*;
@@ -727,6 +1120,29 @@
 //! # }
 //! ```
 //!
+//! A method may return a reference with a generic lifetime if that lifetime
+//! comes from its arguments rather than from `self`, like `&'a T` below.  The
+//! return value must then come from
+//! [`returning`](examples::__mock_MockFoo_Foo::__foo::Expectation::returning)
+//! or a similar method, whose closure may return a borrow of its arguments.
+//! Methods like `return_const` and `return_sequence`, whose values the
+//! `Expectation` would have to own, aren't available.
+//!
+//! ```
+//! # use mockall::*;
+//! #[automock]
+//! trait Selector {
+//!     fn pick<'a>(&self, items: &'a [u32]) -> &'a u32;
+//! }
+//!
+//! # fn main() {
+//! let mut mock = MockSelector::new();
+//! mock.expect_pick()
+//!     .returning(|items| items.iter().max().unwrap());
+//! assert_eq!(&7, mock.pick(&[3, 7, 5]));
+//! # }
+//! ```
+//!
 //! ## Generic traits and structs
 //!
 //! Mocking generic structs and generic traits is not a problem.  The mock
@@ -835,10 +1251,12 @@
 //! ## Static methods
 //!
 //! Mockall can also mock static methods.  But be careful!  The expectations are
-//! global.  If you want to use a static method in multiple tests, you must
-//! provide your own synchronization.  For ordinary methods, expectations are
-//! set on the mock object.  But static methods don't have any mock object.
-//! Instead, you must create a `Context` object just to set their expectations.
+//! global.  For ordinary methods, expectations are set on the mock object.  But
+//! static methods don't have any mock object.  Instead, you must create a
+//! `Context` object just to set their expectations.
+//! When the `Context` drops, its expectations are cleared, even if the test is
+//! panicking.  And a panic from one test won't cause later tests that use the
+//! same static method to fail.
 //!
 //! ```
 //! # use mockall::*;
@@ -852,6 +1270,65 @@
 //! assert_eq!(99, MockA::foo());
 //! ```
 //!
+//! Only one `Context` for each static method may exist at a time.  Creating a
+//! second one will panic, rather than silently mixing two tests' expectations.
+//! If multiple tests use the same static method, create their `Context`s with
+//! the `_context_locked` function instead.  It waits for any other `Context` for
+//! that method to drop, so those tests will take turns.  All of a mock's
+//! locked `Context`s share one lock, which the thread that holds it may take
+//! again.  So a test may lock several methods' `Context`s, in any order,
+//! without deadlocking against other tests.
+//!
+//! ```
+//! # use mockall::*;
+//! #[automock]
+//! pub trait A {
+//!     fn foo() -> u32;
+//! }
+//!
+//! #[test]
+//! fn test1() {
+//!     let ctx = MockA::foo_context_locked();
+//!     ctx.expect().return_const(1u32);
+//!     assert_eq!(1, MockA::foo());
+//! }
+//!
+//! #[test]
+//! fn test2() {
+//!     let ctx = MockA::foo_context_locked();
+//!     ctx.expect().return_const(2u32);
+//!     assert_eq!(2, MockA::foo());
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! Alternatively, the `_context_thread_local` function creates a `Context`
+//! whose expectations apply only to calls made from the current thread.  Tests
+//! that use thread-local `Context`s can run concurrently, because they don't
+//! share any expectations.  If the code under test calls the static method
+//! from another thread, first attach to that thread the handle returned by the
+//! `Context`'s `thread_handle` method.  The thread stays attached until the
+//! guard returned by `attach` drops.
+//!
+//! ```
+//! # use mockall::*;
+//! # use std::thread;
+//! #[automock]
+//! pub trait A {
+//!     fn foo() -> u32;
+//! }
+//!
+//! let ctx = MockA::foo_context_thread_local();
+//! ctx.expect().return_const(1u32);
+//! assert_eq!(1, MockA::foo());
+//!
+//! let handle = ctx.thread_handle();
+//! thread::spawn(move || {
+//!     let _attached = handle.attach();
+//!     assert_eq!(1, MockA::foo());
+//! }).join().unwrap();
+//! ```
+//!
 //! A common pattern is mocking a trait with a constructor method.  In this case,
 //! you can easily set the mock constructor method to return a mock object.
 //!
@@ -1083,27 +1560,31 @@
 //!
 //! ## Crate features
 //!
-//! Mockall has a **nightly** feature.  Currently this feature has two
+//! Mockall has a **nightly** feature.  Currently this feature has three
 //! effects:
 //!
 //! * The compiler will produce better error messages.
 //!
-//! * Expectations for methods whose return type implements `Default` needn't
-//!   have their return values explicitly set.  Instead, they will automatically
-//!   return the default value.
+//! * Panic messages and the call history will show the values of generic
+//!   arguments that implement `Debug`, even without a `Debug` bound.  Without
+//!   **nightly**, they only show those arguments' type names.
+//!
+//! * Expectations for methods with generic return types needn't have their
+//!   return values explicitly set, if the type implements `Default`.  Without
+//!   **nightly**, that only works for concrete return types.
 //!
 //! With **nightly** enabled, you can omit the return value like this:
 #![cfg_attr(feature = "nightly", doc = "```")]
 #![cfg_attr(not(feature = "nightly"), doc = "```should_panic")]
 //! # use mockall::*;
 //! #[automock]
-//! trait Foo {
-//!     fn foo(&self) -> Vec<u32>;
+//! trait Foo<T: 'static> {
+//!     fn foo(&self) -> T;
 //! }
 //!
-//! let mut mock = MockFoo::new();
+//! let mut mock = MockFoo::<u32>::new();
 //! mock.expect_foo();
-//! assert!(mock.foo().is_empty());
+//! assert_eq!(0, mock.foo());
 //! ```
 //!
 //! ## Examples
@@ -1112,7 +1593,9 @@
 //! documentation on the autogenerated methods, see
 //! [`examples`](examples).
 //!
+//! [`Call`]: Call
 //! [`Predicate`]: trait.Predicate.html
+//! [`Prerequisite`]: Prerequisite
 //! [`Sequence`]: Sequence
 //! [`cfg-if`]: https://crates.io/crates/cfg-if
 //! [`function`]: predicate/fn.function.html
@@ -1130,14 +1613,23 @@
 use downcast::*;
 use std::{
     any,
+    cell::RefCell,
+    collections::HashMap,
     fmt::{self, Debug, Formatter},
+    hash::Hash,
     marker::PhantomData,
     ops::{Range, RangeFrom, RangeFull, RangeInclusive, RangeTo,
           RangeToInclusive},
+    panic,
     sync::{
         Arc,
-        atomic::{AtomicUsize, Ordering}
+        Condvar,
+        Mutex,
+        MutexGuard,
+        PoisonError,
+        atomic::{AtomicBool, AtomicUsize, Ordering}
     },
+    thread
 };
 
 #[doc(hidden)]
@@ -1327,8 +1819,68 @@ pub use mockall_derive::automock;
 /// ```
 pub use mockall_derive::mock;
 
+/// Run a trait method's provided body instead of mocking it.
+///
+/// Normally, [`automock`] and [`mock!`] mock every method of a trait, even
+/// those with default implementations.  A method marked `#[passthrough]`
+/// instead runs its provided body whenever no expectation matches a call.
+/// That body will usually call other methods, which remain mocked.
+/// Expectations set for the method itself still take precedence.  In
+/// [`mock!`], the body must be repeated, because the macro can't see the
+/// trait's definition.
+///
+/// Passthrough isn't yet supported for generic methods.
+///
+/// # Examples
+///
+/// ```
+/// # use mockall::*;
+/// #[automock]
+/// trait Foo {
+///     fn foo(&self) -> u32;
+///     #[mockall::passthrough]
+///     fn twice(&self) -> u32 {
+///         self.foo() * 2
+///     }
+/// }
+///
+/// let mut mock = MockFoo::new();
+/// mock.expect_foo()
+///     .return_const(5u32);
+/// assert_eq!(10, mock.twice());
+/// ```
+///
+/// With [`mock!`]:
+/// ```
+/// # use mockall::*;
+/// trait Foo {
+///     fn foo(&self) -> u32;
+///     fn twice(&self) -> u32 {
+///         self.foo() * 2
+///     }
+/// }
+///
+/// mock! {
+///     Bar {}
+///     impl Foo for Bar {
+///         fn foo(&self) -> u32;
+///         #[mockall::passthrough]
+///         fn twice(&self) -> u32 {
+///             self.foo() * 2
+///         }
+///     }
+/// }
+/// # fn main() {}
+/// ```
+///
+/// [`mock!`]: macro.mock.html
+pub use mockall_derive::passthrough;
+
 #[doc(hidden)]
-pub trait AnyExpectations : Any + Send + Sync {}
+pub trait AnyExpectations : Any + Send + Sync {
+    /// Check the call counts of every expectation, without panicking
+    fn verify(&self) -> Vec<Error>;
+}
 downcast!(dyn AnyExpectations);
 
 #[doc(hidden)]
@@ -1341,6 +1893,54 @@ pub trait ReturnDefault<O> {
 #[doc(hidden)]
 pub struct DefaultReturner<O>(PhantomData<O>);
 
+impl<O> DefaultReturner<O> {
+    /// Create a `DefaultReturner`, whether or not `O` implements `Default`.
+    pub fn new() -> Self {
+        DefaultReturner(PhantomData)
+    }
+}
+
+/// Returns default values on stable Rust, using autoref-based specialization.
+///
+/// When called like `(&&DefaultReturner::<O>::new()).return_default()`, method
+/// resolution prefers this trait's impl on `&DefaultReturner` whenever `O` is
+/// known to implement `Default`, and falls back to [`ReturnDefaultFallback`]'s
+/// impl on `DefaultReturner` otherwise.  That works for concrete types, but
+/// not for generic ones, which need the `nightly` feature.
+#[doc(hidden)]
+pub trait ReturnDefaultViaDefault<O> {
+    fn maybe_return_default(&self) -> Option<O>;
+    fn return_default(&self) -> Result<O, &'static str>;
+}
+
+impl<O: Default> ReturnDefaultViaDefault<O> for &DefaultReturner<O> {
+    fn maybe_return_default(&self) -> Option<O> {
+        Some(O::default())
+    }
+
+    fn return_default(&self) -> Result<O, &'static str> {
+        Ok(O::default())
+    }
+}
+
+/// The fallback for [`ReturnDefaultViaDefault`].  It defers to
+/// [`ReturnDefault`], which can use specialization with the `nightly` feature.
+#[doc(hidden)]
+pub trait ReturnDefaultFallback<O> {
+    fn maybe_return_default(&self) -> Option<O>;
+    fn return_default(&self) -> Result<O, &'static str>;
+}
+
+impl<O> ReturnDefaultFallback<O> for DefaultReturner<O> {
+    fn maybe_return_default(&self) -> Option<O> {
+        <Self as ReturnDefault<O>>::maybe_return_default()
+    }
+
+    fn return_default(&self) -> Result<O, &'static str> {
+        <Self as ReturnDefault<O>>::return_default()
+    }
+}
+
 ::cfg_if::cfg_if! {
     if #[cfg(feature = "nightly")] {
         impl<O> ReturnDefault<O> for DefaultReturner<O> {
@@ -1369,37 +1969,749 @@ pub struct DefaultReturner<O>(PhantomData<O>);
             }
 
             fn return_default() -> Result<O, &'static str> {
-                Err("Returning default values requires the \"nightly\" feature")
+                Err("Can only return default values for types that impl std::Default, and for generic types only with the \"nightly\" feature")
             }
         }
     }
 }
 
-#[doc(hidden)]
-pub struct MaybeDebugger<'a, T>(pub &'a T);
-::cfg_if::cfg_if! {
-    if #[cfg(feature = "nightly")] {
-        impl<'a, T> Debug for MaybeDebugger<'a, T> {
-            default fn fmt(&self, f: &mut Formatter<'_>)
-                -> Result<(), fmt::Error>
-            {
-                write!(f, "?")
-            }
-        }
-        impl<'a, T: Debug> Debug for MaybeDebugger<'a, T> {
-            fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+/// Stores the arguments of the calls that an expectation handles, for later
+/// inspection.
+///
+/// Attach a `Captor` to an expectation with its `capture` method.  Every call
+/// that the expectation handles will store an owned copy of the selected
+/// argument.  Arguments whose types are generic or contain lifetimes can't be
+/// captured.  See [`Argument captors`](index.html#argument-captors).
+///
+/// `Captor` is a cheap handle, like an `Arc`.  Its clones all share the same
+/// values.
+pub struct Captor<T>(Arc<Mutex<Vec<T>>>);
+
+impl<T> Captor<T> {
+    /// Create a `Captor` with no values.
+    pub fn new() -> Self {
+        Captor(Arc::new(Mutex::new(Vec::new())))
+    }
+
+    /// Have any values been captured?
+    pub fn is_empty(&self) -> bool {
+        self.0.lock().unwrap().is_empty()
+    }
+
+    /// Return the most recently captured value, if any.
+    pub fn last(&self) -> Option<T> where T: Clone {
+        self.0.lock().unwrap().last().cloned()
+    }
+
+    /// How many values have been captured?
+    pub fn len(&self) -> usize {
+        self.0.lock().unwrap().len()
+    }
+
+    /// Return all of the captured values, oldest first.
+    pub fn values(&self) -> Vec<T> where T: Clone {
+        self.0.lock().unwrap().clone()
+    }
+
+    /// Return a function that stores a value, which must be a `T`.
+    #[doc(hidden)]
+    pub fn storer(&self)
+        -> Box<dyn Fn(Box<dyn any::Any + Send>) + Send + Sync>
+        where T: Send + 'static
+    {
+        let values = self.0.clone();
+        Box::new(move |v| {
+            let v = v.downcast::<T>().expect("captured the wrong type");
+            values.lock().unwrap().push(*v);
+        })
+    }
+}
+
+impl<T> Clone for Captor<T> {
+    fn clone(&self) -> Self {
+        Captor(self.0.clone())
+    }
+}
+
+impl<T: Debug> Debug for Captor<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Captor").field(&*self.0.lock().unwrap()).finish()
+    }
+}
+
+impl<T> Default for Captor<T> {
+    fn default() -> Self {
+        Captor::new()
+    }
+}
+
+/// Describes how to capture an argument of type `A`
+#[doc(hidden)]
+pub struct CaptureFn<A: ?Sized> {
+    /// The type that will be captured: `A`'s owned form
+    pub type_id: any::TypeId,
+    pub type_name: &'static str,
+    /// Make an owned copy of the argument
+    pub convert: fn(&A) -> Box<dyn any::Any + Send>
+}
+
+/// Captures arguments whose types implement `ToOwned`, using autoref-based
+/// specialization.
+///
+/// Use it like `(&&Capturer::<A>::new()).capturer()`, in the same way as
+/// [`DefaultReturner`].  Only use it for `'static` types.
+#[doc(hidden)]
+pub struct Capturer<A: ?Sized>(PhantomData<A>);
+
+impl<A: ?Sized> Capturer<A> {
+    pub fn new() -> Self {
+        Capturer(PhantomData)
+    }
+}
+
+impl<A: ?Sized> Default for Capturer<A> {
+    fn default() -> Self {
+        Capturer::new()
+    }
+}
+
+#[doc(hidden)]
+pub trait CaptureViaToOwned<A: ?Sized> {
+    fn capturer(&self) -> Option<CaptureFn<A>>;
+}
+
+impl<A> CaptureViaToOwned<A> for &Capturer<A>
+    where A: ToOwned + ?Sized,
+          A::Owned: Send + 'static
+{
+    fn capturer(&self) -> Option<CaptureFn<A>> {
+        Some(CaptureFn {
+            type_id: any::TypeId::of::<A::Owned>(),
+            type_name: any::type_name::<A::Owned>(),
+            convert: |a| Box::new(a.to_owned())
+        })
+    }
+}
+
+#[doc(hidden)]
+pub trait CaptureFallback<A: ?Sized> {
+    fn capturer(&self) -> Option<CaptureFn<A>>;
+}
+
+impl<A: ?Sized> CaptureFallback<A> for Capturer<A> {
+    fn capturer(&self) -> Option<CaptureFn<A>> {
+        None
+    }
+}
+
+/// Runs callbacks that expectations have deferred, when the test chooses to.
+///
+/// Pass a `Trigger` to an expectation's `invoke_arg_later` method.  Each call
+/// that the expectation handles will then store an invocation of the
+/// callback argument, and [`fire`](Trigger::fire) will run it.  This is useful
+/// for testing APIs that take completion handlers.  See
+/// [`Callback arguments`](index.html#callback-arguments).
+///
+/// `Trigger` is a cheap handle, like an `Arc`.  Its clones all share the same
+/// pending invocations.  Any thread may fire them, except for invocations of
+/// callbacks that aren't `Send`, which must be fired on the thread that called
+/// the mock method.
+#[derive(Clone, Default)]
+pub struct Trigger(Arc<Mutex<Vec<Invocation>>>);
+
+/// A deferred invocation of a callback
+enum Invocation {
+    /// The callback and its arguments are `Send`, so any thread may run it.
+    Send(Box<dyn FnOnce() + Send>),
+    /// Only the thread that stored it may run it.
+    Local(Fragile<Box<dyn FnOnce()>>)
+}
+
+impl Trigger {
+    /// Create a `Trigger` with no pending invocations.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store an invocation, to run when the `Trigger` fires
+    #[doc(hidden)]
+    pub fn defer(&self, f: Box<dyn FnOnce()>) {
+        self.0.lock().unwrap().push(Invocation::Local(Fragile::new(f)));
+    }
+
+    /// Store an invocation that any thread may run when the `Trigger` fires
+    #[doc(hidden)]
+    pub fn defer_send(&self, f: Box<dyn FnOnce() + Send>) {
+        self.0.lock().unwrap().push(Invocation::Send(f));
+    }
+
+    /// Run every pending invocation, oldest first, and return how many there
+    /// were.
+    ///
+    /// Invocations stored while firing, for example by callbacks that call
+    /// the mock method again, are left for the next call to `fire`.
+    pub fn fire(&self) -> usize {
+        let pending = std::mem::take(&mut *self.0.lock().unwrap());
+        let n = pending.len();
+        for invocation in pending {
+            match invocation {
+                Invocation::Send(f) => f(),
+                Invocation::Local(f) => (f.into_inner())()
+            }
+        }
+        n
+    }
+
+    /// How many invocations are waiting for [`fire`](Trigger::fire)?
+    pub fn pending(&self) -> usize {
+        self.0.lock().unwrap().len()
+    }
+}
+
+impl Debug for Trigger {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Trigger")
+            .field("pending", &self.pending())
+            .finish()
+    }
+}
+
+/// Describes a callback argument that an expectation can invoke
+#[derive(Clone, Copy, Debug)]
+#[doc(hidden)]
+pub struct CallbackType {
+    /// The type of the callback's arguments, as a tuple
+    pub args: any::TypeId,
+    pub args_name: &'static str,
+    /// Can the callback be stored, to invoke later?
+    pub deferrable: bool
+}
+
+impl CallbackType {
+    pub fn new<A: 'static>(deferrable: bool) -> Self {
+        CallbackType {
+            args: any::TypeId::of::<A>(),
+            args_name: any::type_name::<A>(),
+            deferrable
+        }
+    }
+}
+
+/// Produces a fresh tuple of arguments for each invocation of a callback
+#[doc(hidden)]
+pub type ArgsFn = Box<dyn Fn() -> Box<dyn any::Any + Send> + Send + Sync>;
+
+/// Check that argument number `index` of `method` is a callback that takes
+/// `args`, and return a function that clones them.  `callback` is `None` if
+/// `method` has no such argument.
+#[doc(hidden)]
+pub fn invoke_args<A>(method: &str, index: usize,
+                      callback: Option<Option<CallbackType>>, args: A,
+                      later: bool) -> ArgsFn
+    where A: Clone + Send + 'static
+{
+    let callback = match callback {
+        None => panic!("{}: has no argument {}", method, index),
+        Some(None) => panic!(
+            "{}: argument {} can't be invoked.  Only Box<dyn Fn> and fn arguments whose arguments are 'static types can be",
+            method, index),
+        Some(Some(callback)) => callback
+    };
+    if callback.args != any::TypeId::of::<A>() {
+        panic!("{}: argument {} takes arguments {}, not {}", method, index,
+               callback.args_name, any::type_name::<A>());
+    }
+    if later && !callback.deferrable {
+        panic!("{}: argument {} isn't 'static, so it can't be invoked later",
+               method, index);
+    }
+    let args = Mutex::new(args);
+    Box::new(move || Box::new(args.lock().unwrap().clone()))
+}
+
+/// A `&mut` argument that an expectation writes through, with its type erased
+#[doc(hidden)]
+pub enum OutArg<'a> {
+    Value(&'a mut dyn any::Any),
+    /// The elements of a slice
+    Slice(Vec<&'a mut dyn any::Any>)
+}
+
+/// The type of a `&mut` argument that an expectation can write through
+#[derive(Clone, Copy, Debug)]
+#[doc(hidden)]
+pub enum OutArgType {
+    Value(any::TypeId, &'static str),
+    /// A slice, with the type of its elements
+    Slice(any::TypeId, &'static str)
+}
+
+impl OutArgType {
+    pub fn value<T: 'static>() -> Self {
+        OutArgType::Value(any::TypeId::of::<T>(), any::type_name::<T>())
+    }
+
+    pub fn slice<T: 'static>() -> Self {
+        OutArgType::Slice(any::TypeId::of::<T>(), any::type_name::<T>())
+    }
+}
+
+/// Writes a value through a `&mut` argument, or explains why it can't
+#[doc(hidden)]
+pub type ArgWrite =
+    Box<dyn Fn(OutArg<'_>) -> Result<(), String> + Send + Sync>;
+
+/// Check that argument number `index` of `method` can be written with a `T`,
+/// and return a function that writes `value` through it.  `arg` is the
+/// argument's type, if it is a `&mut` of a `'static` type.
+#[doc(hidden)]
+pub fn set_arg<T>(method: &str, index: usize, arg: Option<Option<OutArgType>>,
+                  value: T) -> ArgWrite
+    where T: Clone + Send + 'static
+{
+    match check_out_arg::<T>(method, index, arg) {
+        OutArgType::Value(..) => (),
+        OutArgType::Slice(..) => panic!(
+            "{}: argument {} is a slice.  Use copy_into_arg instead",
+            method, index)
+    }
+    let value = Mutex::new(value);
+    Box::new(move |arg| {
+        if let OutArg::Value(arg) = arg {
+            *arg.downcast_mut::<T>().unwrap() = value.lock().unwrap().clone();
+        }
+        Ok(())
+    })
+}
+
+/// Like [`set_arg`], but copy `values` into the start of a slice argument.
+#[doc(hidden)]
+pub fn copy_into<T>(method: &str, index: usize,
+                    arg: Option<Option<OutArgType>>, values: Vec<T>)
+    -> ArgWrite
+    where T: Clone + Send + 'static
+{
+    match check_out_arg::<T>(method, index, arg) {
+        OutArgType::Value(..) => panic!(
+            "{}: argument {} is not a slice.  Use set_arg_mut instead",
+            method, index),
+        OutArgType::Slice(..) => ()
+    }
+    let values = Mutex::new(values);
+    Box::new(move |arg| {
+        if let OutArg::Slice(mut arg) = arg {
+            let values = values.lock().unwrap();
+            if arg.len() < values.len() {
+                return Err(format!(
+                    "copied {} elements into argument {}, which has room for only {}",
+                    values.len(), index, arg.len()));
+            }
+            for (dst, src) in arg.iter_mut().zip(values.iter()) {
+                *dst.downcast_mut::<T>().unwrap() = src.clone();
+            }
+        }
+        Ok(())
+    })
+}
+
+/// `arg` is `None` if `method` has no such argument.
+fn check_out_arg<T: 'static>(method: &str, index: usize,
+                             arg: Option<Option<OutArgType>>) -> OutArgType
+{
+    let arg = match arg {
+        None => panic!("{}: has no argument {}", method, index),
+        Some(None) => panic!(
+            "{}: argument {} can't be written.  Only &mut arguments of 'static types can be",
+            method, index),
+        Some(Some(arg)) => arg
+    };
+    let (type_id, type_name, desc) = match arg {
+        OutArgType::Value(id, name) => (id, name, "&mut "),
+        OutArgType::Slice(id, name) => (id, name, "a slice of ")
+    };
+    if type_id != any::TypeId::of::<T>() {
+        panic!("{}: argument {} is {}{}, not {}", method, index, desc,
+               type_name, any::type_name::<T>());
+    }
+    arg
+}
+
+/// What a scripted expectation does once it has returned all of its values.
+///
+/// Set it with an expectation's `when_exhausted` method.  See
+/// [`Scripted return values`](index.html#scripted-return-values).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Exhausted {
+    /// Panic, like an expectation that was called too many times.  This is
+    /// the default.
+    Panic,
+    /// Keep returning the last value.  Methods that return by value can only
+    /// do that for types that implement `Clone` and `Send`, and for generic
+    /// types not at all.
+    RepeatLast,
+    /// Stop matching calls, so that later expectations can handle them.  To
+    /// know when to stop, it must peek one value ahead of a `returning_iter`
+    /// iterator.
+    FallThrough
+}
+
+// Deriving Default for enums requires Rust 1.62
+#[allow(clippy::derivable_impls)]
+impl Default for Exhausted {
+    fn default() -> Self {
+        Exhausted::Panic
+    }
+}
+
+/// Converts a value to the type that it already is.  Lets `return_sequence`
+/// infer the types of literals from the method's return type.
+#[doc(hidden)]
+pub trait Is<T> {
+    fn into_is(self) -> T;
+}
+
+impl<T> Is<T> for T {
+    fn into_is(self) -> T {
+        self
+    }
+}
+
+/// State shared between a scripted expectation and its return values
+#[derive(Default)]
+#[doc(hidden)]
+pub struct ScriptState {
+    policy: Mutex<Exhausted>,
+    /// Has the script run out of values?
+    empty: AtomicBool,
+    /// Peeks at the script's next value, and reports whether there is one
+    probe: Mutex<Option<Box<dyn FnMut() -> bool + Send>>>
+}
+
+impl ScriptState {
+    /// Should the expectation stop matching calls?
+    ///
+    /// Only a `FallThrough` script needs to know before it is called, so only
+    /// it peeks at its next value.
+    pub fn is_exhausted(&self) -> bool {
+        if self.policy() != Exhausted::FallThrough {
+            return false;
+        }
+        if !self.empty.load(Ordering::Relaxed) {
+            if let Some(probe) = self.probe.lock().unwrap().as_mut() {
+                if !probe() {
+                    self.set_empty();
+                }
+            }
+        }
+        self.empty.load(Ordering::Relaxed)
+    }
+
+    fn policy(&self) -> Exhausted {
+        *self.policy.lock().unwrap()
+    }
+
+    pub fn set_policy(&self, policy: Exhausted) {
+        *self.policy.lock().unwrap() = policy;
+    }
+
+    fn set_empty(&self) {
+        self.empty.store(true, Ordering::Relaxed);
+    }
+
+    fn set_probe(&self, probe: Box<dyn FnMut() -> bool + Send>) {
+        *self.probe.lock().unwrap() = Some(probe);
+    }
+}
+
+/// Clones the last value of a script, if its type is `Clone` and `Send`.
+///
+/// Use it like `(&&Repeater::<O>::new()).repeater()`, in the same way as
+/// [`DefaultReturner`].
+#[derive(Default)]
+#[doc(hidden)]
+pub struct Repeater<O>(PhantomData<O>);
+
+impl<O> Repeater<O> {
+    pub fn new() -> Self {
+        Repeater(PhantomData)
+    }
+}
+
+/// A function that turns a script's last value into a source of copies
+#[doc(hidden)]
+pub type RepeatFn<O> = fn(&O) -> Box<dyn FnMut() -> O + Send>;
+
+#[doc(hidden)]
+pub trait RepeatViaClone<O> {
+    fn repeater(&self) -> Option<RepeatFn<O>>;
+}
+
+impl<O: Clone + Send + 'static> RepeatViaClone<O> for &Repeater<O> {
+    fn repeater(&self) -> Option<RepeatFn<O>> {
+        Some(|o| {
+            let o = o.clone();
+            Box::new(move || o.clone())
+        })
+    }
+}
+
+#[doc(hidden)]
+pub trait RepeatFallback<O> {
+    fn repeater(&self) -> Option<RepeatFn<O>>;
+}
+
+impl<O> RepeatFallback<O> for Repeater<O> {
+    fn repeater(&self) -> Option<RepeatFn<O>> {
+        None
+    }
+}
+
+/// Return a function that returns each of `values` in turn, and then follows
+/// the policy in `state`.
+///
+/// `values` is only advanced as the function is called, so its end is found
+/// by the first call after the last value.  The exception is a `FallThrough`
+/// policy, which peeks one value ahead whenever a call is matched.
+#[doc(hidden)]
+pub fn script<S, O>(values: Box<dyn Iterator<Item = S> + Send>,
+                    repeater: Option<RepeatFn<O>>,
+                    state: Arc<ScriptState>)
+    -> Box<dyn FnMut() -> Result<O, &'static str> + Send>
+    where S: Is<O> + Send + 'static,
+          O: 'static
+{
+    let values = Arc::new(Mutex::new(values.peekable()));
+    let probe_values = values.clone();
+    // Any script that this one replaces may have run out already.
+    state.empty.store(false, Ordering::Relaxed);
+    state.set_probe(Box::new(move || {
+        probe_values.lock().unwrap().peek().is_some()
+    }));
+    let mut last: Option<Box<dyn FnMut() -> O + Send>> = None;
+    Box::new(move || {
+        // Once empty, leave the iterator alone, since it might not be fused.
+        let next = if state.empty.load(Ordering::Relaxed) {
+            None
+        } else {
+            values.lock().unwrap().next()
+        };
+        if let Some(s) = next {
+            let o = s.into_is();
+            // Keep a copy in case this turns out to be the last value.
+            if state.policy() == Exhausted::RepeatLast {
+                last = repeater.map(|r| r(&o));
+            }
+            return Ok(o);
+        }
+        state.set_empty();
+        if state.policy() != Exhausted::RepeatLast {
+            Err("ran out of scripted return values")
+        } else if let Some(f) = last.as_mut() {
+            Ok(f())
+        } else {
+            Err("ran out of scripted return values, and can only repeat the last one for types that impl Clone and Send")
+        }
+    })
+}
+
+/// Owns the values that a `returning` closure creates for a method that
+/// returns by reference, so that each reference stays valid for as long as
+/// the expectation is borrowed.
+#[doc(hidden)]
+pub struct RefStore<T> {
+    values: Mutex<Vec<Box<T>>>,
+    // Handing out `&T` to other threads requires `T: Sync`, which the Mutex
+    // alone would not.
+    _sync: PhantomData<T>
+}
+
+impl<T> RefStore<T> {
+    /// Store `value`, and return a reference to it.
+    pub fn store(&self, value: T) -> &T {
+        let mut values = self.values.lock().unwrap();
+        values.push(Box::new(value));
+        let p: *const T = &**values.last().unwrap();
+        // Safe because each value is boxed, so it never moves even if the Vec
+        // does, and values are only ever removed by dropping the RefStore,
+        // which can't happen while `self` is borrowed.
+        unsafe { &*p }
+    }
+}
+
+impl<T> Default for RefStore<T> {
+    fn default() -> Self {
+        RefStore {
+            values: Mutex::new(Vec::new()),
+            _sync: PhantomData
+        }
+    }
+}
+
+/// Creates a [`VarMap`]'s value for a type-erased key
+type VarInit<V> = Box<dyn FnMut(&dyn any::Any) -> V + Send + Sync>;
+
+/// The values that a `return_var_keyed` expectation returns by mutable
+/// reference, one per key.
+///
+/// Only the keys' type is erased, so that `VarMap<V>` is `Send` and `Sync`
+/// whenever `V` is.
+#[doc(hidden)]
+pub struct VarMap<V> {
+    keys: Box<dyn KeyIndex + Send + Sync>,
+    /// The keys' real type, and its name for error messages
+    key_type: any::TypeId,
+    key_name: &'static str,
+    init: VarInit<V>,
+    values: Vec<V>
+}
+
+impl<V> VarMap<V> {
+    pub fn new<K, F>(mut init: F) -> Self
+        where K: Eq + Hash + Send + Sync + 'static,
+              F: FnMut(&K) -> V + Send + Sync + 'static
+    {
+        VarMap {
+            keys: Box::new(HashMap::<K, usize>::new()),
+            key_type: any::TypeId::of::<K>(),
+            key_name: any::type_name::<K>(),
+            init: Box::new(move |k| init(k.downcast_ref::<K>().unwrap())),
+            values: Vec::new()
+        }
+    }
+
+    /// Return the value for `key`, creating it if necessary.
+    pub fn entry(&mut self, key: Box<dyn any::Any>) -> &mut V {
+        let i = match self.keys.index(&*key) {
+            Some(i) => i,
+            None => {
+                let v = (self.init)(&*key);
+                self.values.push(v);
+                self.keys.insert(key, self.values.len() - 1);
+                self.values.len() - 1
+            }
+        };
+        &mut self.values[i]
+    }
+
+    /// Return the value for `key`, if it has been created.  Panics if `key`
+    /// isn't the type of `method`'s keys, which could never be found.
+    pub fn get<K: 'static>(&self, method: &str, key: &K) -> Option<&V> {
+        if any::TypeId::of::<K>() != self.key_type {
+            panic!("{}: keys are {}, not {}", method, self.key_name,
+                   any::type_name::<K>());
+        }
+        self.keys.index(key).map(|i| &self.values[i])
+    }
+}
+
+/// Maps the keys of a [`VarMap`] to indices into its values
+trait KeyIndex {
+    /// Look up `key`, which needn't be the right type.
+    fn index(&self, key: &dyn any::Any) -> Option<usize>;
+
+    fn insert(&mut self, key: Box<dyn any::Any>, i: usize);
+}
+
+impl<K: Eq + Hash + 'static> KeyIndex for HashMap<K, usize> {
+    fn index(&self, key: &dyn any::Any) -> Option<usize> {
+        key.downcast_ref::<K>().and_then(|k| self.get(k)).cloned()
+    }
+
+    fn insert(&mut self, key: Box<dyn any::Any>, i: usize) {
+        HashMap::insert(self, *key.downcast::<K>().unwrap(), i);
+    }
+}
+
+/// A script of values that are returned by reference
+#[doc(hidden)]
+pub struct RefScript<O> {
+    values: Vec<O>,
+    /// Index of the next value to return
+    next: AtomicUsize,
+    state: Arc<ScriptState>
+}
+
+impl<O> RefScript<O> {
+    pub fn new(values: Vec<O>, state: Arc<ScriptState>) -> Self {
+        // Any script that this one replaces may have run out already.
+        state.empty.store(values.is_empty(), Ordering::Relaxed);
+        RefScript{values, next: AtomicUsize::new(0), state}
+    }
+
+    /// Return the next value, or follow the policy if there are none left.
+    pub fn next(&self) -> Result<&O, &'static str> {
+        let i = self.next.fetch_add(1, Ordering::Relaxed);
+        if i + 1 >= self.values.len() {
+            self.state.set_empty();
+        }
+        if let Some(o) = self.values.get(i) {
+            Ok(o)
+        } else if self.state.policy() == Exhausted::RepeatLast &&
+            !self.values.is_empty()
+        {
+            Ok(&self.values[self.values.len() - 1])
+        } else {
+            Err("ran out of scripted return values")
+        }
+    }
+}
+
+/// Formats a mock method's argument, if it implements `Debug`.
+///
+/// Use it like `(&&MaybeDebugger(&x)).debug_string()`.  On stable Rust, method
+/// resolution picks [`MaybeDebugViaDebug`] whenever the argument's type is
+/// known to implement `Debug`.  Otherwise it falls back to
+/// [`MaybeDebugFallback`], which prints the type's name instead.  With the
+/// `nightly` feature, the fallback uses specialization, so it can format
+/// generic arguments too.
+#[doc(hidden)]
+pub struct MaybeDebugger<'a, T>(pub &'a T);
+::cfg_if::cfg_if! {
+    if #[cfg(feature = "nightly")] {
+        impl<'a, T> Debug for MaybeDebugger<'a, T> {
+            default fn fmt(&self, f: &mut Formatter<'_>)
+                -> Result<(), fmt::Error>
+            {
+                write!(f, "<{}>", any::type_name::<T>())
+            }
+        }
+        impl<'a, T: Debug> Debug for MaybeDebugger<'a, T> {
+            fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
                 self.0.fmt(f)
             }
         }
     } else {
         impl<'a, T> Debug for MaybeDebugger<'a, T> {
             fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
-                write!(f, "?")
+                write!(f, "<{}>", any::type_name::<T>())
             }
         }
     }
 }
 
+#[doc(hidden)]
+pub trait MaybeDebugViaDebug {
+    fn debug_string(&self) -> String;
+}
+
+impl<'a, T: Debug> MaybeDebugViaDebug for &MaybeDebugger<'a, T> {
+    fn debug_string(&self) -> String {
+        format!("{:?}", self.0)
+    }
+}
+
+/// The fallback for [`MaybeDebugViaDebug`].
+#[doc(hidden)]
+pub trait MaybeDebugFallback {
+    fn debug_string(&self) -> String;
+}
+
+impl<'a, T> MaybeDebugFallback for MaybeDebugger<'a, T> {
+    fn debug_string(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
 // Though it's not entirely correct, we treat usize::max_value() as
 // approximately infinity.
 #[derive(Debug)]
@@ -1510,6 +2822,13 @@ impl Times {
         self.count.load(Ordering::Relaxed) >= self.range.0.start
     }
 
+    /// Has this expectation been called an allowed number of times?
+    /// Unlike `is_satisfied`, this also checks the maximum, which can only be
+    /// exceeded if a panic from an earlier call was caught.
+    pub fn is_valid(&self) -> bool {
+        self.range.0.contains(&self.count.load(Ordering::Relaxed))
+    }
+
     /// The minimum number of times that this expectation must be called
     pub fn minimum(&self) -> usize {
         self.range.0.start
@@ -1546,47 +2865,601 @@ impl Key {
     }
 }
 
+/// Counter used to order every mock call made by the process
+static NEXT_CALL_INDEX: AtomicUsize = AtomicUsize::new(0);
+
+/// A record of a single call to a mocked method.
+///
+/// Every mocked method keeps a history of its calls, which can be retrieved
+/// with the generated `calls_*` methods, or with `Context::calls` for static
+/// methods.  See [`Call history`](index.html#call-history).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Call {
+    index: usize,
+    args: Vec<String>,
+    expectation: Option<usize>,
+    description: Option<String>,
+}
+
+impl Call {
+    /// The call's arguments, rendered with their `Debug` implementations.
+    /// Arguments whose types don't implement `Debug` are shown as their type
+    /// names in angle brackets, like `"<my_crate::Thing>"`.
+    pub fn args(&self) -> &[String] {
+        &self.args
+    }
+
+    /// Description of the expectation that handled this call: its name, if it
+    /// was given one with `named`, or else its matcher.  `None` if no
+    /// expectation matched.
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// Position of the expectation that handled this call within the order
+    /// that the method's expectations were created, or `None` if no
+    /// expectation matched.
+    pub fn expectation(&self) -> Option<usize> {
+        self.expectation
+    }
+
+    /// Global index of this call.  Every call to any mock method gets a
+    /// larger index than the calls that came before it, so indices may be
+    /// used to compare the order of calls to different methods.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+/// Storage for a mock method's [`Call`] history
+#[derive(Debug, Default)]
+#[doc(hidden)]
+pub struct CallHistory(Mutex<Vec<Call>>);
+
+impl CallHistory {
+    /// Return a copy of every recorded call, oldest first
+    pub fn calls(&self) -> Vec<Call> {
+        self.0.lock().unwrap().clone()
+    }
+
+    /// Discard all recorded calls
+    pub fn clear(&self) {
+        self.0.lock().unwrap().clear();
+    }
+
+    /// Record a new call
+    pub fn record(&self, args: Vec<String>, expectation: Option<usize>,
+                  description: Option<String>)
+    {
+        let index = NEXT_CALL_INDEX.fetch_add(1, Ordering::Relaxed);
+        self.0.lock().unwrap()
+            .push(Call{index, args, expectation, description});
+    }
+}
+
+/// What kind of problem an [`Error`] describes
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ErrorKind {
+    /// An expectation was called fewer or more times than allowed
+    CallCount,
+    /// A call matched no expectation
+    NoMatch,
+    /// An expectation was called out of its `Sequence`'s order
+    Sequence,
+    /// An expectation was called before one of its `after` prerequisites was
+    /// satisfied
+    Prerequisite
+}
+
+/// A problem with a mock method's expectations or calls.
+///
+/// Returned by the generated `verify` methods of mock objects and of `Context`
+/// objects, which check expectations without panicking and without discarding
+/// them.  Besides expectations that weren't called the right number of times,
+/// they report the violations that earlier calls panicked with.  See
+/// [`Verifying without panicking`](index.html#verifying-without-panicking).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Error {
+    kind: ErrorKind,
+    method: String,
+    description: String,
+    location: Option<&'static std::panic::Location<'static>>,
+    expected: Range<usize>,
+    count: usize,
+    args: Vec<Vec<String>>,
+    /// What the violating call panicked with, for every kind but `CallCount`
+    message: Option<String>
+}
+
+impl Error {
+    #[doc(hidden)]
+    pub fn new(method: &str, description: String,
+               location: &'static std::panic::Location<'static>,
+               times: &Times, args: Vec<Vec<String>>) -> Self
+    {
+        Error {
+            kind: ErrorKind::CallCount,
+            method: method.to_owned(),
+            description,
+            location: Some(location),
+            expected: times.range.0.clone(),
+            count: times.count(),
+            args,
+            message: None
+        }
+    }
+
+    /// A call, described like `MockFoo::foo(5)`, that matched no expectation
+    /// for the reason `why`.
+    #[doc(hidden)]
+    pub fn no_match(method: &str, call: &str, args: Vec<String>, why: &str)
+        -> Self
+    {
+        Error {
+            kind: ErrorKind::NoMatch,
+            method: method.to_owned(),
+            description: String::new(),
+            location: None,
+            expected: 0..0,
+            count: 0,
+            args: vec![args],
+            message: Some(format!("{}: No matching expectation found{}",
+                                  call, why))
+        }
+    }
+
+    /// A call to an expectation that violated a `Sequence` or a
+    /// prerequisite, with the message that it panicked with.
+    #[doc(hidden)]
+    pub fn violation(kind: ErrorKind, method: &str, description: String,
+                     location: &'static std::panic::Location<'static>,
+                     times: &Times, message: String) -> Self
+    {
+        Error {
+            kind,
+            method: method.to_owned(),
+            description,
+            location: Some(location),
+            expected: times.range.0.clone(),
+            count: times.count(),
+            args: Vec::new(),
+            message: Some(message)
+        }
+    }
+
+    /// For a `CallCount` error, the arguments of each call that the
+    /// expectation handled, oldest first, rendered like [`Call::args`].  For
+    /// a `NoMatch` error, the arguments of the unmatched call.  Otherwise
+    /// empty.
+    pub fn args(&self) -> &[Vec<String>] {
+        &self.args
+    }
+
+    /// How many times the expectation had been called.  Zero for a `NoMatch`
+    /// error, which involves no expectation.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Description of the expectation: its name, if it was given one with
+    /// `named`, or else its matcher.  Empty for a `NoMatch` error.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// The range of call counts that would have satisfied the expectation.
+    /// Expectations with no upper limit end at `usize::MAX`.  Empty for a
+    /// `NoMatch` error.
+    pub fn expected(&self) -> Range<usize> {
+        self.expected.clone()
+    }
+
+    /// What kind of problem this is
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// Where the expectation was created, or `None` for a `NoMatch` error.
+    pub fn location(&self) -> Option<&'static std::panic::Location<'static>> {
+        self.location
+    }
+
+    /// Name of the mocked method, like `MockFoo::foo`
+    pub fn method(&self) -> &str {
+        &self.method
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(message) = &self.message {
+            return f.write_str(message);
+        }
+        write!(f, "{}: Expectation({}) called {} time(s) which is ",
+            self.method, self.description, self.count)?;
+        if self.count < self.expected.start {
+            write!(f, "fewer than expected {}", self.expected.start)?;
+        } else {
+            write!(f, "more than the expected {}", self.expected.end - 1)?;
+        }
+        write!(f, " (set at {})", self.location.unwrap())
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Violations that a mock method's calls panicked with, kept for `verify`
+#[derive(Debug, Default)]
+#[doc(hidden)]
+pub struct ErrorLog(Mutex<Vec<Error>>);
+
+impl ErrorLog {
+    pub fn clear(&self) {
+        self.0.lock().unwrap().clear();
+    }
+
+    pub fn errors(&self) -> Vec<Error> {
+        self.0.lock().unwrap().clone()
+    }
+
+    pub fn push(&self, error: Error) {
+        self.0.lock().unwrap().push(error);
+    }
+}
+
+/// A function called with the message of every mock violation
+type FailureHandler = Arc<dyn Fn(&str) + Send + Sync>;
+
+lazy_static! {
+    static ref FAILURE_HANDLER: Mutex<Option<FailureHandler>> =
+        Mutex::new(None);
+}
+
+thread_local! {
+    /// The message of the violation that this thread is panicking with, if
+    /// any.  Distinguishes violations from other panics, such as those raised
+    /// by `returning` closures.
+    // const initializers for thread_local require Rust 1.59
+    #[allow(clippy::missing_const_for_thread_local)]
+    static VIOLATION: RefCell<Option<String>> = RefCell::new(None);
+}
+
+/// Install a function to be called with the message of every mock violation,
+/// such as a call that matches no expectation, a call count or sequence
+/// violation, or an expectation that was called too few times by the time it
+/// was checkpointed or dropped.
+///
+/// The handler runs on the thread that raised the violation, just before it
+/// panics, and it replaces the default behavior of recording the violation
+/// on the mock object.  See
+/// [`Failures on other threads`](index.html#failures-on-other-threads).
+///
+/// The handler applies to the whole process, not just to the current test.
+/// When `cargo test` runs tests in parallel, it also receives the violations
+/// of tests running on other threads.
+///
+/// # Examples
+///
+/// ```
+/// # use mockall::*;
+/// # use std::sync::{Arc, Mutex};
+/// let failures = Arc::new(Mutex::new(Vec::new()));
+/// let failures2 = failures.clone();
+/// mockall::set_failure_handler(move |msg| {
+///     failures2.lock().unwrap().push(msg.to_owned())
+/// });
+/// # mockall::clear_failure_handler();
+/// ```
+pub fn set_failure_handler<F>(handler: F)
+    where F: Fn(&str) + Send + Sync + 'static
+{
+    *FAILURE_HANDLER.lock().unwrap() = Some(Arc::new(handler));
+}
+
+/// Remove any handler installed by [`set_failure_handler`], restoring the
+/// default behavior.
+pub fn clear_failure_handler() {
+    *FAILURE_HANDLER.lock().unwrap() = None;
+}
+
+/// Lock one of a static method's global mutexes, or a spy's real object,
+/// recovering the lock if it was poisoned.  The panic that poisoned it has
+/// already failed its own test or call, and shouldn't fail every later one.
+#[doc(hidden)]
+pub fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Serializes the locked `Context`s of all static methods of one mock struct or
+/// mocked module, so tests that lock several of them can't deadlock each other.
+/// The thread that holds it may lock more `Context`s without waiting.
+#[derive(Debug, Default)]
+#[doc(hidden)]
+pub struct ContextLock {
+    /// The thread that holds the lock, and how many `Context`s it has locked
+    owner: Mutex<Option<(thread::ThreadId, usize)>>,
+    released: Condvar
+}
+
+impl ContextLock {
+    /// Wait until no other thread holds the lock, then take it.
+    fn acquire(&self) {
+        let current = thread::current().id();
+        let mut owner = lock(&self.owner);
+        loop {
+            match &mut *owner {
+                Some((id, count)) if *id == current => {
+                    *count += 1;
+                    return;
+                },
+                Some(_) => {
+                    owner = self.released.wait(owner)
+                        .unwrap_or_else(PoisonError::into_inner);
+                },
+                None => {
+                    *owner = Some((current, 1));
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Undo one `acquire`, waking the waiters once the lock is free.
+    fn release(&self) {
+        let mut owner = lock(&self.owner);
+        if let Some((_, count)) = &mut *owner {
+            *count -= 1;
+            if *count == 0 {
+                *owner = None;
+                self.released.notify_all();
+            }
+        }
+    }
+}
+
+/// Ensures that each static method has at most one live `Context` at a time.
+#[derive(Debug, Default)]
+#[doc(hidden)]
+pub struct ContextTracker {
+    /// The thread that created the live `Context`, if any, and the
+    /// [`ContextLock`] that `Context` holds, if it was locked
+    live: Mutex<Option<(thread::ThreadId, Option<&'static ContextLock>)>>,
+    released: Condvar
+}
+
+impl ContextTracker {
+    /// Register a new `Context`.  If another is already live, either wait for
+    /// it to drop or panic, depending on whether `mock_lock` is given.  A
+    /// waiting `Context` first takes `mock_lock`, and holds it until it drops.
+    /// Waiting for a `Context` that the current thread created would never
+    /// end, so that panics too.
+    pub fn acquire(&self, method: &str, locked_fn: &str,
+                   mock_lock: Option<&'static ContextLock>)
+    {
+        let current = thread::current().id();
+        if let Some(mock_lock) = mock_lock {
+            mock_lock.acquire();
+        }
+        let mut live = lock(&self.live);
+        if let Some(mock_lock) = mock_lock {
+            if live.map(|(id, _)| id) == Some(current) {
+                drop(live);
+                mock_lock.release();
+                panic!("{}: This thread already holds a Context for this method, so {} would wait for it forever.",
+                    method, locked_fn);
+            }
+            while live.is_some() {
+                live = self.released.wait(live)
+                    .unwrap_or_else(PoisonError::into_inner);
+            }
+        } else if live.is_some() {
+            drop(live);
+            panic!("{}: A Context for this method already exists.  Tests that share a static method must not run concurrently; use {} to wait for the other Context to drop.",
+                method, locked_fn);
+        }
+        *live = Some((current, mock_lock));
+    }
+
+    /// Run `f`, then unregister a dropped `Context`, release the
+    /// [`ContextLock`] it holds, if any, and wake any waiters.  The `Context`
+    /// is unregistered even if `f` panics.
+    pub fn release_after<F: FnOnce()>(&self, f: F) {
+        struct Release<'a>(&'a ContextTracker);
+
+        impl Drop for Release<'_> {
+            fn drop(&mut self) {
+                let live = lock(&self.0.live).take();
+                self.0.released.notify_all();
+                if let Some((_, Some(mock_lock))) = live {
+                    mock_lock.release();
+                }
+            }
+        }
+
+        let _release = Release(self);
+        f()
+    }
+}
+
+/// Panic with the message of a mock violation.
+#[doc(hidden)]
+pub fn fail(msg: String) -> ! {
+    let handler = FAILURE_HANDLER.lock().unwrap().clone();
+    if let Some(handler) = handler {
+        handler(&msg);
+    }
+    VIOLATION.with(|v| *v.borrow_mut() = Some(msg.clone()));
+    panic!("{}", msg);
+}
+
+/// Violations raised by one mock method's calls, which haven't yet been
+/// reported on the thread that owns the mock.
+#[derive(Clone, Debug, Default)]
+#[doc(hidden)]
+pub struct Violations(Arc<Mutex<Vec<(thread::ThreadId, String)>>>);
+
+impl Violations {
+    /// Panic with every violation that was raised on a different thread than
+    /// the current one.  Violations raised on this thread have already
+    /// panicked here.
+    pub fn reraise(&self) {
+        if thread::panicking() {
+            return;
+        }
+        let current = thread::current().id();
+        let msgs = std::mem::take(&mut *self.0.lock().unwrap())
+            .into_iter()
+            .filter(|(id, _)| *id != current)
+            .map(|(_, msg)| msg)
+            .collect::<Vec<_>>();
+        if !msgs.is_empty() {
+            panic!("{}", msgs.join("\n"));
+        }
+    }
+
+    /// Run a mock method, reporting any violation that it panics with.
+    pub fn watch<R, F: FnOnce() -> R>(&self, f: F) -> R {
+        VIOLATION.with(|v| v.borrow_mut().take());
+        panic::catch_unwind(panic::AssertUnwindSafe(f))
+            .unwrap_or_else(|payload| {
+                if let Some(msg) = VIOLATION.with(|v| v.borrow_mut().take()) {
+                    // `fail` already reported it to any installed handler
+                    if FAILURE_HANDLER.lock().unwrap().is_none() {
+                        self.0.lock().unwrap()
+                            .push((thread::current().id(), msg));
+                    }
+                }
+                panic::resume_unwind(payload)
+            })
+    }
+}
+
 #[doc(hidden)]
 pub struct SeqHandle {
     inner: Arc<SeqInner>,
-    seq: usize
+    /// The position of this expectation's step within the sequence
+    seq: usize,
+    /// This expectation's index within its step
+    member: usize
 }
 
 impl SeqHandle {
-    /// Tell the Sequence that this expectation has been fully satisfied
-    pub fn satisfy(&self) {
-        self.inner.satisfy(self.seq);
+    /// Update the description used to identify this expectation in error
+    /// messages.
+    pub fn describe(&self, desc: String) {
+        self.inner.state.lock().unwrap()
+            .steps[self.seq].members[self.member].desc = desc;
     }
 
-    /// Verify that this handle was called in the correct order
-    pub fn verify(&self, desc: &str) {
-        self.inner.verify(self.seq, desc);
+    /// Tell the Sequence whether this expectation has been called at least its
+    /// minimum number of times.
+    pub fn satisfy(&self, satisfied: bool) {
+        self.inner.state.lock().unwrap()
+            .steps[self.seq].members[self.member].satisfied = satisfied;
+    }
+
+    /// Verify that this handle was called in the correct order, returning
+    /// the violation's message if it wasn't.
+    pub fn verify(&self, desc: &str) -> Result<(), String> {
+        self.inner.verify(self.seq, desc)
     }
 }
 
 #[derive(Default)]
 struct SeqInner {
-    satisfaction_level: AtomicUsize,
+    state: Mutex<SeqState>
+}
+
+#[derive(Default)]
+struct SeqState {
+    /// The position of the step that is currently allowed to be called
+    position: usize,
+    steps: Vec<SeqStep>,
+    /// Is the last step an unordered group that is still accepting members?
+    group_open: bool
+}
+
+/// One position in a sequence: either a single expectation or an unordered
+/// group of them.
+struct SeqStep {
+    /// The group's name, if this step is an unordered group
+    group: Option<String>,
+    members: Vec<SeqMember>
+}
+
+impl SeqStep {
+    /// Describe why this step is not yet satisfied, if it isn't.
+    fn outstanding(&self) -> Option<String> {
+        let mut unsatisfied = self.members.iter()
+            .filter(|m| !m.satisfied)
+            .peekable();
+        unsatisfied.peek()?;
+        Some(match &self.group {
+            None => format!("{} has not yet been satisfied",
+                unsatisfied.next().unwrap().desc),
+            Some(name) => {
+                let mut msg = format!(
+                    "Group \"{}\" has outstanding members:", name);
+                for m in unsatisfied {
+                    msg.push_str(&format!("\n    {}", m.desc));
+                }
+                msg
+            }
+        })
+    }
+}
+
+struct SeqMember {
+    desc: String,
+    /// Has this expectation been called at least its minimum number of times?
+    satisfied: bool
 }
 
 impl SeqInner {
-    /// Record the call identified by `seq` as fully satisfied.
-    fn satisfy(&self, seq: usize) {
-        let old_sl = self.satisfaction_level.fetch_add(1, Ordering::Relaxed);
-        assert_eq!(old_sl, seq, "Method sequence violation.  Was an already-satisfied method called another time?");
+    /// Add a new expectation to the end of the sequence, or to its open group,
+    /// returning the position of its step and its index within that step.
+    fn push(&self) -> (usize, usize) {
+        let mut state = self.state.lock().unwrap();
+        if !state.group_open {
+            state.steps.push(SeqStep{group: None, members: Vec::new()});
+        }
+        let seq = state.steps.len() - 1;
+        let step = &mut state.steps[seq];
+        step.members.push(SeqMember{desc: String::new(), satisfied: false});
+        (seq, step.members.len() - 1)
     }
 
-    /// Verify that the call identified by `seq` was called in the correct order
-    fn verify(&self, seq: usize, desc: &str) {
-        assert_eq!(seq, self.satisfaction_level.load(Ordering::Relaxed),
-            "{}: Method sequence violation", desc)
+    /// Verify that the call identified by `seq` was called in the correct
+    /// order, and make it the current position of the sequence.
+    ///
+    /// Moving past a step is only allowed if all of its expectations have
+    /// already been satisfied.
+    fn verify(&self, seq: usize, desc: &str) -> Result<(), String> {
+        let mut state = self.state.lock().unwrap();
+        if seq < state.position {
+            Err(format!(
+                "{}: Method sequence violation.  A later method in the sequence has already been called",
+                desc))
+        } else if let Some(outstanding) = state.steps[state.position..seq]
+            .iter()
+            .find_map(SeqStep::outstanding)
+        {
+            Err(format!("{}: Method sequence violation.  {}",
+                desc, outstanding))
+        } else {
+            state.position = seq;
+            Ok(())
+        }
     }
 }
 
 /// Used to enforce that mock calls must happen in the sequence specified.
 ///
-/// Each expectation must expect to be called a fixed number of times.  Once
-/// satisfied, the next expectation in the sequence will expect to be called.
+/// Once an expectation in the sequence has been called at least its minimum
+/// number of times, the next expectation in the sequence may be called.
+/// Calling a later expectation before an earlier one has been satisfied, or
+/// calling an earlier expectation after a later one has been called, is a
+/// sequence violation.
 ///
 /// # Examples
 /// ```
@@ -1615,25 +3488,96 @@ impl SeqInner {
 /// mock1.bar();
 /// ```
 ///
-/// It is an error to add an expectation to a `Sequence` if its call count is
-/// unspecified.
-/// ```should_panic(expected = "with an exact call count")
+/// Expectations in a `Sequence` may have a range of call counts, too.  For
+/// example, a method may be retried a few times before the next one is called.
+/// ```
+/// # use mockall::*;
+/// #[automock]
+/// trait Foo {
+///     fn foo(&self);
+///     fn bar(&self);
+/// }
+/// let mut seq = Sequence::new();
+///
+/// let mut mock = MockFoo::new();
+/// mock.expect_foo()
+///     .times(1..=3)
+///     .returning(|| ())
+///     .in_sequence(&mut seq);
+/// mock.expect_bar()
+///     .times(1)
+///     .returning(|| ())
+///     .in_sequence(&mut seq);
+///
+/// mock.foo();
+/// mock.foo();
+/// mock.bar();
+/// ```
+///
+/// But it is a sequence violation to move on before an expectation's minimum
+/// call count has been reached.
+/// ```should_panic(expected = "Method sequence violation")
 /// # use mockall::*;
 /// #[automock]
 /// trait Foo {
 ///     fn foo(&self);
+///     fn bar(&self);
 /// }
 /// let mut seq = Sequence::new();
 ///
 /// let mut mock = MockFoo::new();
 /// mock.expect_foo()
+///     .times(2..)
+///     .returning(|| ())
+///     .in_sequence(&mut seq);
+/// mock.expect_bar()
+///     .returning(|| ())
+///     .in_sequence(&mut seq);
+///
+/// mock.foo();
+/// mock.bar();     // panics!
+/// ```
+///
+/// Several expectations can occupy a single position in the sequence as an
+/// unordered group.
+/// ```
+/// # use mockall::*;
+/// #[automock]
+/// trait Foo {
+///     fn open(&self);
+///     fn write(&self, x: u32);
+///     fn close(&self);
+/// }
+/// let mut seq = Sequence::new();
+///
+/// let mut mock = MockFoo::new();
+/// mock.expect_open()
+///     .times(1)
+///     .returning(|| ())
+///     .in_sequence(&mut seq);
+/// seq.begin_group("writes");
+/// for i in 0..3 {
+///     mock.expect_write()
+///         .with(predicate::eq(i))
+///         .times(1)
+///         .returning(|_| ())
+///         .in_sequence(&mut seq);
+/// }
+/// seq.end_group();
+/// mock.expect_close()
+///     .times(1)
 ///     .returning(|| ())
-///     .in_sequence(&mut seq);  // panics!
+///     .in_sequence(&mut seq);
+///
+/// mock.open();
+/// mock.write(2);
+/// mock.write(0);
+/// mock.write(1);
+/// mock.close();
 /// ```
 #[derive(Default)]
 pub struct Sequence {
     inner: Arc<SeqInner>,
-    next_seq: usize,
 }
 
 impl Sequence {
@@ -1645,8 +3589,115 @@ impl Sequence {
     /// can call it.
     #[doc(hidden)]
     pub fn next_handle(&mut self) -> SeqHandle {
-        let handle = SeqHandle{inner: self.inner.clone(), seq: self.next_seq};
-        self.next_seq += 1;
-        handle
+        let (seq, member) = self.inner.push();
+        SeqHandle{inner: self.inner.clone(), seq, member}
+    }
+
+    /// Begin an unordered group within this sequence.
+    ///
+    /// Every expectation added to the sequence until
+    /// [`end_group`](#method.end_group) occupies the same position.  They may
+    /// be called in any order relative to each other, but all of them must be
+    /// satisfied before the sequence can advance past the group.  `name`
+    /// identifies the group in error messages.
+    pub fn begin_group(&mut self, name: &str) {
+        let mut state = self.inner.state.lock().unwrap();
+        state.steps.push(SeqStep{
+            group: Some(name.to_owned()),
+            members: Vec::new()
+        });
+        state.group_open = true;
+    }
+
+    /// End the unordered group begun by
+    /// [`begin_group`](#method.begin_group).
+    pub fn end_group(&mut self) {
+        self.inner.state.lock().unwrap().group_open = false;
+    }
+}
+
+/// A token that lets other expectations depend on an expectation.
+///
+/// A `Prerequisite` is obtained from an expectation's `prerequisite` method.
+/// Other expectations can then use their `after` method to require that it be
+/// satisfied before they are called.  Unlike a [`Sequence`], this imposes only
+/// a partial order.
+///
+/// # Examples
+/// ```
+/// # use mockall::*;
+/// #[automock]
+/// trait Foo {
+///     fn write(&self, x: u32);
+///     fn commit(&self);
+/// }
+///
+/// let mut mock = MockFoo::new();
+/// let write_a = mock.expect_write()
+///     .with(predicate::eq(1))
+///     .times(1)
+///     .returning(|_| ())
+///     .prerequisite();
+/// let write_b = mock.expect_write()
+///     .with(predicate::eq(2))
+///     .times(1)
+///     .returning(|_| ())
+///     .prerequisite();
+/// mock.expect_commit()
+///     .times(1)
+///     .returning(|| ())
+///     .after(&[&write_a, &write_b]);
+///
+/// // The writes may happen in any order, but both must precede the commit
+/// mock.write(2);
+/// mock.write(1);
+/// mock.commit();
+/// ```
+#[derive(Clone, Default)]
+pub struct Prerequisite {
+    inner: Arc<PrerequisiteInner>
+}
+
+#[derive(Default)]
+struct PrerequisiteInner {
+    desc: Mutex<String>,
+    satisfied: AtomicBool
+}
+
+impl Prerequisite {
+    /// Not for public consumption, but it must be public so the generated code
+    /// can call it.
+    #[doc(hidden)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Update the description used to identify this prerequisite's
+    /// expectation in error messages.
+    #[doc(hidden)]
+    pub fn describe(&self, desc: String) {
+        *self.inner.desc.lock().unwrap() = desc;
+    }
+
+    /// Tell the prerequisite whether its expectation has been called at least
+    /// its minimum number of times.
+    #[doc(hidden)]
+    pub fn satisfy(&self, satisfied: bool) {
+        self.inner.satisfied.store(satisfied, Ordering::Relaxed);
+    }
+
+    /// Verify that this prerequisite has been satisfied, before allowing a
+    /// dependent expectation to be called.  Returns the violation's message
+    /// if it hasn't.
+    #[doc(hidden)]
+    pub fn verify(&self, desc: &str) -> Result<(), String> {
+        if self.inner.satisfied.load(Ordering::Relaxed) {
+            Ok(())
+        } else {
+            let prereq_desc = self.inner.desc.lock().unwrap().clone();
+            Err(format!(
+                "{}: Prerequisite violation.  {} has not yet been satisfied",
+                desc, prereq_desc))
+        }
     }
 }
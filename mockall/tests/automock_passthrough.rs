@@ -0,0 +1,86 @@
+// vim: tw=80
+//! Trait methods marked passthrough run their provided bodies
+#![deny(warnings)]
+
+use mockall::*;
+
+#[automock]
+trait Foo {
+    fn foo(&self, x: u32) -> u32;
+    fn set(&mut self, x: u32);
+    #[mockall::passthrough]
+    fn bar(&self, x: u32) -> u32 {
+        self.foo(x) + 1
+    }
+    #[passthrough]
+    fn set_twice(&mut self, x: u32) {
+        self.set(x);
+        self.set(x);
+    }
+    #[mockall::passthrough]
+    fn empty(&self) {}
+    #[mockall::passthrough]
+    fn new_foo() -> u32 {
+        Self::baz() * 2
+    }
+    fn baz() -> u32;
+}
+
+#[test]
+fn empty_body() {
+    let mock = MockFoo::new();
+    mock.empty();
+}
+
+/// An expectation overrides the provided body
+#[test]
+fn expectation() {
+    let mut mock = MockFoo::new();
+    mock.expect_bar()
+        .with(predicate::eq(1))
+        .return_const(42u32);
+    mock.expect_foo()
+        .return_const(5u32);
+    assert_eq!(42, mock.bar(1));
+    assert_eq!(6, mock.bar(2));
+}
+
+#[test]
+fn mutable() {
+    let mut mock = MockFoo::new();
+    mock.expect_set()
+        .with(predicate::eq(3))
+        .times(2)
+        .return_const(());
+    mock.set_twice(3);
+}
+
+#[test]
+fn passthrough() {
+    let mut mock = MockFoo::new();
+    mock.expect_foo()
+        .with(predicate::eq(4))
+        .return_const(5u32);
+    assert_eq!(6, mock.bar(4));
+}
+
+#[test]
+fn recorded() {
+    let mut mock = MockFoo::new();
+    mock.expect_foo()
+        .return_const(5u32);
+    mock.bar(4);
+    let calls = mock.calls_bar();
+    assert_eq!(1, calls.len());
+    assert_eq!(None, calls[0].expectation());
+    assert_eq!(calls[0].args(), ["4"]);
+    assert_eq!(1, mock.calls_foo().len());
+}
+
+#[test]
+fn static_method() {
+    let ctx = MockFoo::baz_context();
+    ctx.expect()
+        .return_const(21u32);
+    assert_eq!(42, MockFoo::new_foo());
+}
@@ -21,3 +21,14 @@ fn returning() {
     mock.foo(&mut x);
     assert_eq!(42, x);
 }
+
+#[test]
+fn set_arg_mut() {
+    let mut mock = MockT::new();
+    let mut x = 5;
+    mock.expect_foo()
+        .set_arg_mut(0, 42u32)
+        .return_const(());
+    mock.foo(&mut x);
+    assert_eq!(42, x);
+}
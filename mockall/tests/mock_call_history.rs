@@ -0,0 +1,149 @@
+// vim: tw=80
+//! Every mocked method records the history of its calls
+#![deny(warnings)]
+
+use mockall::*;
+use std::sync::Mutex;
+
+trait Bar {
+    fn bar(&self, x: u32);
+}
+
+mock! {
+    Foo {
+        fn foo(&self, x: u32, y: &str) -> u32;
+        fn baz(&mut self, x: u32) -> &mut u32;
+        fn generic<T: std::fmt::Debug + 'static>(&self, t: T);
+        fn bean(x: u32) -> u32;
+    }
+    impl Bar for Foo {
+        fn bar(&self, x: u32);
+    }
+}
+
+lazy_static! {
+    static ref BEAN_MTX: Mutex<()> = Mutex::new(());
+}
+
+#[test]
+fn checkpoint_clears_history() {
+    let mut mock = MockFoo::new();
+    mock.expect_foo().return_const(0u32);
+    mock.foo(1, "one");
+    mock.checkpoint();
+    assert!(mock.calls_foo().is_empty());
+}
+
+#[test]
+fn empty() {
+    let mock = MockFoo::new();
+    assert!(mock.calls_foo().is_empty());
+    assert!(mock.calls_generic::<u8>().is_empty());
+}
+
+#[test]
+fn generic_method() {
+    let mut mock = MockFoo::new();
+    mock.expect_generic::<u16>().return_const(());
+    mock.expect_generic::<i8>().return_const(());
+    mock.generic(5u16);
+    mock.generic(-1i8);
+    mock.generic(6u16);
+
+    let calls = mock.calls_generic::<u16>();
+    assert_eq!(2, calls.len());
+    assert_eq!(calls[0].args(), ["5"]);
+    assert_eq!(calls[1].args(), ["6"]);
+    assert_eq!(1, mock.calls_generic::<i8>().len());
+}
+
+/// Calls are recorded even for generic parameters that have no expectations
+#[test]
+fn generic_method_unexpected() {
+    let mut mock = MockFoo::new();
+    mock.expect_generic::<u16>().return_const(());
+    mock.generic(5u16);
+    let r = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        mock.generic(7u32);
+    }));
+    assert!(r.is_err());
+    let calls = mock.calls_generic::<u32>();
+    assert_eq!(1, calls.len());
+    assert_eq!(calls[0].args(), ["7"]);
+    assert_eq!(None, calls[0].expectation());
+    assert_eq!(1, mock.calls_generic::<u16>().len());
+}
+
+#[test]
+fn matching_expectation() {
+    let mut mock = MockFoo::new();
+    mock.expect_foo()
+        .with(predicate::eq(1), predicate::always())
+        .return_const(10u32);
+    mock.expect_foo()
+        .with(predicate::eq(2), predicate::always())
+        .return_const(20u32);
+    mock.foo(2, "two");
+    mock.foo(1, "one");
+
+    let calls = mock.calls_foo();
+    assert_eq!(Some(1), calls[0].expectation());
+    assert_eq!(Some(0), calls[1].expectation());
+}
+
+#[test]
+fn order() {
+    let mut mock = MockFoo::new();
+    mock.expect_foo().return_const(0u32);
+    mock.expect_bar().return_const(());
+    mock.foo(1, "one");
+    mock.bar(2);
+    mock.foo(3, "three");
+
+    let foo_calls = mock.calls_foo();
+    let bar_calls = mock.calls_bar();
+    assert_eq!(2, foo_calls.len());
+    assert_eq!(1, bar_calls.len());
+    assert!(foo_calls[0].index() < bar_calls[0].index());
+    assert!(bar_calls[0].index() < foo_calls[1].index());
+}
+
+#[test]
+fn refmut() {
+    let mut mock = MockFoo::new();
+    mock.expect_baz().return_var(5);
+    *mock.baz(1) += 1;
+    *mock.baz(2) += 1;
+    let calls = mock.calls_baz();
+    assert_eq!(2, calls.len());
+    assert_eq!(Some(0), calls[1].expectation());
+}
+
+#[test]
+fn static_method() {
+    let _m = BEAN_MTX.lock().unwrap();
+
+    let ctx = MockFoo::bean_context();
+    ctx.expect().returning(|x| x + 1);
+    MockFoo::bean(4);
+    let calls = ctx.calls();
+    assert_eq!(1, calls.len());
+    assert_eq!(Some(0), calls[0].expectation());
+    ctx.checkpoint();
+    assert!(ctx.calls().is_empty());
+}
+
+#[test]
+fn unmatched() {
+    let mut mock = MockFoo::new();
+    mock.expect_foo()
+        .with(predicate::eq(1), predicate::always())
+        .return_const(0u32);
+    let r = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        mock.foo(2, "two");
+    }));
+    assert!(r.is_err());
+    let calls = mock.calls_foo();
+    assert_eq!(1, calls.len());
+    assert_eq!(None, calls[0].expectation());
+}
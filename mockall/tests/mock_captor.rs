@@ -0,0 +1,132 @@
+// vim: tw=80
+//! Captors store the arguments of the calls that an expectation handles
+#![deny(warnings)]
+
+use mockall::*;
+
+struct NonClone;
+
+mock! {
+    Foo {
+        fn foo(&self, x: u32, name: &str) -> u32;
+        fn gen<T: 'static>(&self, t: T);
+        fn lifetime<'a>(&self, x: &'a [&'a u32]);
+        fn non_clone(&self, x: NonClone);
+        fn refmut(&mut self, x: Vec<u32>) -> &mut u32;
+        fn refer(&self, x: Option<u64>) -> &u32;
+    }
+}
+
+#[test]
+fn basic() {
+    let xs = Captor::<u32>::new();
+    let names = Captor::<String>::new();
+    let mut mock = MockFoo::new();
+    mock.expect_foo()
+        .capture(0, &xs)
+        .capture(1, &names)
+        .return_const(0u32);
+    mock.foo(1, "one");
+    mock.foo(2, "two");
+    assert_eq!(vec![1, 2], xs.values());
+    assert_eq!(Some("two".to_owned()), names.last());
+    assert_eq!(2, names.len());
+}
+
+#[test]
+fn empty() {
+    let xs = Captor::<u32>::default();
+    let mut mock = MockFoo::new();
+    mock.expect_foo()
+        .capture(0, &xs)
+        .return_const(0u32);
+    assert!(xs.is_empty());
+    assert_eq!(None, xs.last());
+}
+
+#[test]
+#[should_panic(expected = "MockFoo::gen: argument 0 can't be captured.")]
+fn generic() {
+    let captor = Captor::<u32>::new();
+    let mut mock = MockFoo::new();
+    mock.expect_gen::<u32>()
+        .capture(0, &captor);
+}
+
+#[test]
+#[should_panic(expected = "MockFoo::lifetime: argument 0 can't be captured.")]
+fn lifetime() {
+    let captor = Captor::<Vec<&'static u32>>::new();
+    let mut mock = MockFoo::new();
+    mock.expect_lifetime()
+        .capture(0, &captor);
+}
+
+#[test]
+#[should_panic(expected = "MockFoo::foo: has no argument 2")]
+fn no_such_argument() {
+    let captor = Captor::<u32>::new();
+    let mut mock = MockFoo::new();
+    mock.expect_foo()
+        .capture(2, &captor);
+}
+
+#[test]
+#[should_panic(expected = "MockFoo::non_clone: argument 0 can't be captured.")]
+fn non_clone() {
+    let captor = Captor::<NonClone>::new();
+    let mut mock = MockFoo::new();
+    mock.expect_non_clone()
+        .capture(0, &captor);
+}
+
+/// Only the calls that the expectation handles are captured, not those that
+/// it merely considers
+#[test]
+fn only_handled_calls() {
+    let captor = Captor::<String>::new();
+    let mut mock = MockFoo::new();
+    mock.expect_foo()
+        .with(predicate::eq(1), predicate::always())
+        .capture(1, &captor)
+        .return_const(0u32);
+    mock.expect_foo()
+        .return_const(0u32);
+    mock.foo(2, "two");
+    mock.foo(1, "one");
+    mock.foo(3, "three");
+    assert_eq!(vec!["one".to_owned()], captor.values());
+}
+
+#[test]
+fn refmut() {
+    let captor = Captor::<Vec<u32>>::new();
+    let mut mock = MockFoo::new();
+    mock.expect_refmut()
+        .capture(0, &captor)
+        .return_var(0);
+    mock.refmut(vec![1, 2]);
+    assert_eq!(Some(vec![1, 2]), captor.last());
+}
+
+#[test]
+fn reference() {
+    let captor = Captor::<Option<u64>>::new();
+    let mut mock = MockFoo::new();
+    mock.expect_refer()
+        .capture(0, &captor)
+        .return_const(0);
+    mock.refer(Some(5));
+    mock.refer(None);
+    assert_eq!(vec![Some(5), None], captor.values());
+}
+
+#[test]
+#[should_panic(expected =
+    "MockFoo::foo: argument 1 is captured as alloc::string::String, not u32")]
+fn wrong_type() {
+    let captor = Captor::<u32>::new();
+    let mut mock = MockFoo::new();
+    mock.expect_foo()
+        .capture(1, &captor);
+}
@@ -0,0 +1,84 @@
+// vim: tw=80
+//! Expectation-related panics should say where the expectation was created
+#![deny(warnings)]
+
+use mockall::*;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+
+struct NonDefault();
+
+mock! {
+    Foo {
+        fn foo(&self, x: u32) -> u32;
+        fn nondefault(&self) -> NonDefault;
+        fn generic<T: 'static>(&self, t: T);
+        fn bar(x: u32) -> u32;
+    }
+}
+
+fn panic_message<F: FnOnce()>(f: F) -> String {
+    let e = catch_unwind(AssertUnwindSafe(f)).unwrap_err();
+    if let Some(s) = e.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        String::from(*e.downcast_ref::<&str>().unwrap())
+    }
+}
+
+#[test]
+fn called_too_often() {
+    let mut mock = MockFoo::new();
+    let line = line!() + 1;
+    mock.expect_foo()
+        .never();
+    let msg = panic_message(|| {mock.foo(0);});
+    assert!(msg.ends_with(&format!("should not have been called (set at {}:{}:10)",
+        file!(), line)), "{}", msg);
+}
+
+#[test]
+fn called_too_few_times() {
+    let mut line = 0;
+    let msg = panic_message(|| {
+        let mut mock = MockFoo::new();
+        line = line!() + 1;
+        mock.expect_foo()
+            .times(2)
+            .return_const(0u32);
+        mock.foo(0);
+    });
+    assert!(msg.ends_with(&format!(
+        "called 1 time(s) which is fewer than expected 2 (set at {}:{}:14)",
+        file!(), line)), "{}", msg);
+}
+
+#[test]
+fn generic_method() {
+    let mut mock = MockFoo::new();
+    let line = line!() + 1;
+    mock.expect_generic::<u16>()
+        .never();
+    let msg = panic_message(|| mock.generic(0u16));
+    assert!(msg.ends_with(&format!("(set at {}:{}:10)", file!(), line)),
+        "{}", msg);
+}
+
+#[test]
+fn no_return_value() {
+    let mut mock = MockFoo::new();
+    let line = line!() + 1;
+    mock.expect_nondefault();
+    let msg = panic_message(|| {mock.nondefault();});
+    assert!(msg.ends_with(&format!("(set at {}:{}:10)", file!(), line)),
+        "{}", msg);
+}
+
+#[test]
+#[should_panic(expected =
+    "should not have been called (set at mockall/tests/mock_expectation_location.rs:")]
+fn static_method() {
+    let ctx = MockFoo::bar_context();
+    ctx.expect()
+        .never();
+    MockFoo::bar(0);
+}
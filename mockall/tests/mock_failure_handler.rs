@@ -0,0 +1,54 @@
+// vim: tw=80
+//! A global failure handler sees violations instead of the mock object
+//!
+//! The handler is process-wide, so this file must hold only one test.
+#![deny(warnings)]
+
+use mockall::*;
+use std::{
+    sync::{Arc, Mutex},
+    thread
+};
+
+mock! {
+    Foo {
+        fn foo(&self, x: u32) -> u32;
+    }
+}
+
+#[test]
+fn handler() {
+    let failures = Arc::new(Mutex::new(Vec::<String>::new()));
+    let failures2 = failures.clone();
+    set_failure_handler(move |msg| {
+        failures2.lock().unwrap().push(msg.to_owned())
+    });
+    let mock = Arc::new(MockFoo::new());
+    let mock2 = mock.clone();
+    thread::spawn(move || mock2.foo(5)).join().unwrap_err();
+    clear_failure_handler();
+    let failures = failures.lock().unwrap();
+    assert_eq!(1, failures.len());
+    assert!(failures[0].starts_with(
+        "MockFoo::foo(5): No matching expectation found"));
+    // Dropping the mock doesn't panic, because the handler took the violation
+    drop(failures);
+    drop(mock);
+
+    // Expectations called too few times are reported when they're dropped
+    let failures = Arc::new(Mutex::new(Vec::<String>::new()));
+    let failures2 = failures.clone();
+    set_failure_handler(move |msg| {
+        failures2.lock().unwrap().push(msg.to_owned())
+    });
+    let mut mock = MockFoo::new();
+    mock.expect_foo()
+        .times(1)
+        .return_const(0u32);
+    thread::spawn(move || drop(mock)).join().unwrap_err();
+    clear_failure_handler();
+    let failures = failures.lock().unwrap();
+    assert_eq!(1, failures.len());
+    assert!(failures[0].starts_with(
+        "MockFoo::foo: Expectation(<anything>) called 0 time(s) which is fewer than expected 1"));
+}
@@ -51,7 +51,7 @@ mod with {
             expected = "MockFoo::foo(4): No matching expectation found"
     ))]
     #[cfg_attr(not(feature = "nightly"), should_panic(
-            expected = "MockFoo::foo(?): No matching expectation found"
+            expected = "MockFoo::foo(<i32>): No matching expectation found"
     ))]
     fn wrong_generic_type() {
         let mut mock = MockFoo::new();
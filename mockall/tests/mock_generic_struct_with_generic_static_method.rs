@@ -52,7 +52,7 @@ fn ctx_checkpoint() {
         expected = "MockFoo::foo3(42, 69): No matching expectation found"
 ))]
 #[cfg_attr(not(feature = "nightly"), should_panic(
-        expected = "MockFoo::foo3(?, ?): No matching expectation found"
+        expected = "MockFoo::foo3(<i32>, <i32>): No matching expectation found"
 ))]
 fn ctx_hygiene() {
     {
@@ -63,8 +63,6 @@ fn ctx_hygiene() {
     MockFoo::foo3(42, 69);
 }
 
-#[cfg_attr(not(feature = "nightly"), ignore)]
-#[cfg_attr(not(feature = "nightly"), allow(unused_must_use))]
 #[test]
 fn return_default() {
     let _m = FOO_MTX.lock().unwrap();
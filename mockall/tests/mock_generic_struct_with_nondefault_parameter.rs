@@ -20,8 +20,6 @@ mock! {
 #[test]
 #[should_panic(expected =
     "MockExternalStruct::foo: Expectation(<anything>) Can only return default values for types that impl std::Default")]
-#[cfg_attr(not(feature = "nightly"), ignore)]
-#[cfg_attr(not(feature = "nightly"), allow(unused_must_use))]
 fn return_default() {
     let mut mock = MockExternalStruct::<NonDefault>::new();
     mock.expect_foo();
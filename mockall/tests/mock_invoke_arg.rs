@@ -0,0 +1,155 @@
+// vim: tw=80
+//! Expectations can invoke callback arguments
+#![deny(warnings)]
+
+use mockall::*;
+use std::{
+    sync::{
+        Arc,
+        Mutex,
+        atomic::{AtomicU32, Ordering}
+    },
+    thread
+};
+
+mock! {
+    Foo {
+        fn boxed(&self, f: Box<dyn FnOnce(u32) -> u32 + Send>);
+        fn fn_ptr(&self, f: fn(u32)) -> u32;
+        fn gen<T: 'static>(&self, f: Box<dyn Fn(T)>);
+        fn mutable<F: FnMut(u32) + 'static>(&self, f: F);
+        fn once<F: FnOnce(String) + 'static>(&self, f: F);
+        fn shared<F: Fn(u32, u32) + 'static>(&self, f: F) -> u32;
+        fn x(&self, x: u32);
+    }
+}
+
+#[test]
+fn boxed_once() {
+    let out = Arc::new(Mutex::new(0));
+    let out2 = out.clone();
+    let mut mock = MockFoo::new();
+    mock.expect_boxed()
+        .invoke_arg(0, (5u32,))
+        .return_const(());
+    mock.boxed(Box::new(move |x| { *out2.lock().unwrap() = x; x }));
+    assert_eq!(5, *out.lock().unwrap());
+}
+
+#[test]
+fn fn_ptr() {
+    static CALLED: AtomicU32 = AtomicU32::new(0);
+    fn f(x: u32) {
+        CALLED.fetch_add(x, Ordering::Relaxed);
+    }
+    let mut mock = MockFoo::new();
+    mock.expect_fn_ptr()
+        .invoke_arg(0, (2u32,))
+        .returning(|f| { f(3); 0 });
+    mock.fn_ptr(f);
+    assert_eq!(5, CALLED.load(Ordering::Relaxed));
+}
+
+#[test]
+#[should_panic(expected = "MockFoo::gen: argument 0 can't be invoked.")]
+fn generic() {
+    let mut mock = MockFoo::new();
+    mock.expect_gen::<u32>()
+        .invoke_arg(0, (5u32,));
+}
+
+#[test]
+fn later() {
+    let out = Arc::new(Mutex::new(Vec::new()));
+    let trigger = Trigger::new();
+    let mut mock = MockFoo::new();
+    mock.expect_once()
+        .invoke_arg_later(0, ("done".to_owned(),), &trigger)
+        .return_const(());
+    let out2 = out.clone();
+    mock.once(move |s| out2.lock().unwrap().push(s));
+    let out2 = out.clone();
+    mock.once(move |s| out2.lock().unwrap().push(s + "!"));
+    assert!(out.lock().unwrap().is_empty());
+    assert_eq!(2, trigger.pending());
+    assert_eq!(2, trigger.fire());
+    assert_eq!(vec!["done", "done!"], *out.lock().unwrap());
+    assert_eq!(0, trigger.fire());
+}
+
+/// Invocations of `Send` callbacks may be fired on a different thread than
+/// the one that called the mock method
+#[test]
+fn later_other_thread() {
+    let out = Arc::new(Mutex::new(0));
+    let trigger = Trigger::new();
+    let mut mock = MockFoo::new();
+    mock.expect_boxed()
+        .invoke_arg_later(0, (5u32,), &trigger)
+        .return_const(());
+    let out2 = out.clone();
+    let mock = thread::spawn(move || {
+        mock.boxed(Box::new(move |x| { *out2.lock().unwrap() = x; x }));
+        mock
+    }).join().unwrap();
+    assert_eq!(1, trigger.fire());
+    assert_eq!(5, *out.lock().unwrap());
+    drop(mock);
+}
+
+/// The return value can't use a callback that was deferred
+#[test]
+#[should_panic(expected =
+    "MockFoo::shared: argument 0 was already taken by invoke_arg_later")]
+fn later_then_returning() {
+    let trigger = Trigger::new();
+    let mut mock = MockFoo::new();
+    mock.expect_shared()
+        .invoke_arg_later(0, (1u32, 2u32), &trigger)
+        .returning(|f| { f(0, 0); 0 });
+    mock.shared(|_, _| ());
+}
+
+#[test]
+fn mutable() {
+    let out = Arc::new(Mutex::new(0));
+    let out2 = out.clone();
+    let mut mock = MockFoo::new();
+    mock.expect_mutable()
+        .invoke_arg(0, (1u32,))
+        .invoke_arg(0, (2u32,))
+        .return_const(());
+    let mut total = 0;
+    mock.mutable(move |x| { total += x; *out2.lock().unwrap() = total; });
+    assert_eq!(3, *out.lock().unwrap());
+}
+
+#[test]
+#[should_panic(expected = "MockFoo::x: argument 0 can't be invoked.")]
+fn not_a_callback() {
+    let mut mock = MockFoo::new();
+    mock.expect_x()
+        .invoke_arg(0, (5u32,));
+}
+
+/// Invoking an Fn callback doesn't prevent the return value from using it
+#[test]
+fn shared() {
+    let out = Arc::new(Mutex::new(0));
+    let out2 = out.clone();
+    let mut mock = MockFoo::new();
+    mock.expect_shared()
+        .invoke_arg(0, (1u32, 2u32))
+        .returning(|f| { f(3, 4); 0 });
+    mock.shared(move |x, y| *out2.lock().unwrap() += x * y);
+    assert_eq!(14, *out.lock().unwrap());
+}
+
+#[test]
+#[should_panic(expected =
+    "MockFoo::once: argument 0 takes arguments (alloc::string::String,), not (&str,)")]
+fn wrong_type() {
+    let mut mock = MockFoo::new();
+    mock.expect_once()
+        .invoke_arg(0, ("done",));
+}
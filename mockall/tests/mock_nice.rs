@@ -0,0 +1,103 @@
+// vim: tw=80
+//! Nice mocks handle unmatched calls with a fallback instead of panicking
+#![deny(warnings)]
+
+use mockall::*;
+
+trait Bar {
+    fn bar(&self, x: u32) -> u32;
+}
+
+mock! {
+    Foo {
+        fn foo(&self, x: u32) -> u32;
+        fn generic<T: 'static>(&self, t: T) -> u32;
+        fn refmut(&mut self) -> &mut u32;
+        fn reference(&self) -> &u32;
+    }
+    impl Bar for Foo {
+        fn bar(&self, x: u32) -> u32;
+    }
+}
+
+/// Checkpointing discards the custom fallback, but the mock stays nice
+#[test]
+fn checkpoint() {
+    let mut mock = MockFoo::nice();
+    mock.fallback_foo().return_const(5u32);
+    mock.checkpoint();
+    assert_eq!(0, mock.foo(0));
+}
+
+#[test]
+fn default_value() {
+    let mock = MockFoo::nice();
+    assert_eq!(0, mock.foo(4));
+    assert_eq!(0, mock.bar(4));
+    assert_eq!(0, *mock.reference());
+}
+
+#[test]
+fn fallback() {
+    let mut mock = MockFoo::nice();
+    mock.fallback_foo().returning(|x| x + 1);
+    mock.fallback_bar().returning(|x| x + 2);
+    assert_eq!(5, mock.foo(4));
+    assert_eq!(6, mock.bar(4));
+}
+
+/// The fallback is only used when no other expectation matches
+#[test]
+fn fallback_last() {
+    let mut mock = MockFoo::nice();
+    mock.expect_foo()
+        .with(predicate::eq(1))
+        .return_const(10u32);
+    mock.fallback_foo().return_const(99u32);
+    assert_eq!(10, mock.foo(1));
+    assert_eq!(99, mock.foo(2));
+}
+
+#[test]
+fn generic_method() {
+    let mut mock = MockFoo::nice();
+    mock.fallback_generic::<u16>().return_const(42u32);
+    assert_eq!(42, mock.generic(7u16));
+}
+
+/// A method may be given a fallback even if its mock isn't nice
+#[test]
+fn not_nice() {
+    let mut mock = MockFoo::new();
+    mock.fallback_foo().return_const(7u32);
+    assert_eq!(7, mock.foo(0));
+}
+
+#[test]
+#[should_panic(expected = "No matching expectation found")]
+fn not_nice_after_checkpoint() {
+    let mut mock = MockFoo::new();
+    mock.fallback_foo().return_const(7u32);
+    mock.checkpoint();
+    mock.foo(0);
+}
+
+#[test]
+fn recorded() {
+    let mut mock = MockFoo::nice();
+    mock.fallback_foo().return_const(0u32);
+    mock.foo(3);
+    let calls = mock.calls_foo();
+    assert_eq!(1, calls.len());
+    assert_eq!(None, calls[0].expectation());
+}
+
+#[test]
+fn reference() {
+    let mut mock = MockFoo::nice();
+    mock.fallback_reference().return_const(8u32);
+    mock.fallback_refmut().return_var(9u32);
+    assert_eq!(8, *mock.reference());
+    *mock.refmut() += 1;
+    assert_eq!(10, *mock.refmut());
+}
@@ -0,0 +1,149 @@
+// vim: tw=80
+//! Expectations can write through `&mut` arguments
+#![deny(warnings)]
+
+use mockall::*;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+struct Header {
+    len: u32
+}
+
+mock! {
+    Foo {
+        fn fill(&self, out: &mut Header);
+        fn gen<T: 'static>(&self, t: &mut T);
+        fn read(&mut self, buf: &mut [u8]) -> usize;
+        fn shared(&self, x: &u32);
+        fn two(&self, x: &mut u32, y: &mut String) -> &u32;
+        fn unsized_str(&self, s: &mut str);
+        fn unsized_dyn(&self, w: &mut dyn std::fmt::Write);
+    }
+}
+
+#[test]
+fn copy_into_arg() {
+    let mut buf = [0u8; 4];
+    let mut mock = MockFoo::new();
+    mock.expect_read()
+        .copy_into_arg(0, &[1u8, 2, 3])
+        .return_const(3usize);
+    assert_eq!(3, mock.read(&mut buf));
+    assert_eq!([1, 2, 3, 0], buf);
+}
+
+#[test]
+#[should_panic(expected =
+    "MockFoo::read: Expectation(<anything>) copied 3 elements into argument 0, which has room for only 2")]
+fn copy_into_arg_too_short() {
+    let mut buf = [0u8; 2];
+    let mut mock = MockFoo::new();
+    mock.expect_read()
+        .copy_into_arg(0, &[1u8, 2, 3])
+        .return_const(3usize);
+    mock.read(&mut buf);
+}
+
+#[test]
+#[should_panic(expected =
+    "MockFoo::fill: argument 0 is not a slice.  Use set_arg_mut instead")]
+fn copy_into_non_slice() {
+    let mut mock = MockFoo::new();
+    mock.expect_fill()
+        .copy_into_arg(0, &[Header::default()]);
+}
+
+#[test]
+#[should_panic(expected = "MockFoo::gen: argument 0 can't be written.")]
+fn generic() {
+    let mut mock = MockFoo::new();
+    mock.expect_gen::<u32>()
+        .set_arg_mut(0, 5u32);
+}
+
+#[test]
+#[should_panic(expected = "MockFoo::fill: has no argument 1")]
+fn no_such_argument() {
+    let mut mock = MockFoo::new();
+    mock.expect_fill()
+        .set_arg_mut(1, Header::default());
+}
+
+/// The return value is computed after the arguments are written
+#[test]
+fn returning() {
+    let mut buf = [0u8; 4];
+    let mut mock = MockFoo::new();
+    mock.expect_read()
+        .copy_into_arg(0, &[7u8, 7])
+        .returning(|buf| buf.iter().filter(|b| **b == 7).count());
+    assert_eq!(2, mock.read(&mut buf));
+}
+
+#[test]
+fn set_arg_mut() {
+    let mut header = Header::default();
+    let mut mock = MockFoo::new();
+    mock.expect_fill()
+        .set_arg_mut(0, Header{len: 42})
+        .return_const(());
+    mock.fill(&mut header);
+    assert_eq!(Header{len: 42}, header);
+}
+
+#[test]
+fn set_arg_mut_reference_return() {
+    let mut x = 0;
+    let mut y = String::new();
+    let mut mock = MockFoo::new();
+    mock.expect_two()
+        .set_arg_mut(0, 1u32)
+        .set_arg_mut(1, "two".to_owned())
+        .return_const(3);
+    assert_eq!(3, *mock.two(&mut x, &mut y));
+    assert_eq!(1, x);
+    assert_eq!("two", y);
+}
+
+#[test]
+#[should_panic(expected =
+    "MockFoo::read: argument 0 is a slice.  Use copy_into_arg instead")]
+fn set_arg_mut_slice() {
+    let mut mock = MockFoo::new();
+    mock.expect_read()
+        .set_arg_mut(0, 5u8);
+}
+
+#[test]
+#[should_panic(expected = "MockFoo::shared: argument 0 can't be written.")]
+fn shared_reference() {
+    let mut mock = MockFoo::new();
+    mock.expect_shared()
+        .set_arg_mut(0, 5u32);
+}
+
+/// Unsized arguments can't be written, but can still be mocked
+#[test]
+#[should_panic(expected = "MockFoo::unsized_str: argument 0 can't be written.")]
+fn unsized_arg() {
+    let mut s = String::from("x");
+    let mut w = String::new();
+    let mut mock = MockFoo::new();
+    mock.expect_unsized_str()
+        .return_const(());
+    mock.expect_unsized_dyn()
+        .return_const(());
+    mock.unsized_str(&mut s);
+    mock.unsized_dyn(&mut w);
+    mock.expect_unsized_str()
+        .set_arg_mut(0, String::new());
+}
+
+#[test]
+#[should_panic(expected =
+    "MockFoo::two: argument 1 is &mut alloc::string::String, not &str")]
+fn wrong_type() {
+    let mut mock = MockFoo::new();
+    mock.expect_two()
+        .set_arg_mut(1, "two");
+}
@@ -0,0 +1,40 @@
+// vim: tw=80
+//! mock! can run a trait method's provided body, if it's repeated
+#![deny(warnings)]
+
+use mockall::*;
+
+trait Foo {
+    fn foo(&self, x: u32) -> u32;
+    fn bar(&self, x: u32) -> u32 {
+        self.foo(x) + 1
+    }
+}
+
+mock! {
+    Baz {}
+    impl Foo for Baz {
+        fn foo(&self, x: u32) -> u32;
+        #[mockall::passthrough]
+        fn bar(&self, x: u32) -> u32 {
+            self.foo(x) + 1
+        }
+    }
+}
+
+#[test]
+fn expectation() {
+    let mut mock = MockBaz::new();
+    mock.expect_bar()
+        .return_const(42u32);
+    assert_eq!(42, mock.bar(1));
+}
+
+#[test]
+fn passthrough() {
+    let mut mock = MockBaz::new();
+    mock.expect_foo()
+        .with(predicate::eq(4))
+        .return_const(5u32);
+    assert_eq!(6, mock.bar(4));
+}
@@ -0,0 +1,134 @@
+// vim: tw=80
+//! A method that returns a reference borrowed from one of its arguments, rather
+//! than from `self`, can return a borrow of its arguments.
+#![deny(warnings)]
+
+use mockall::*;
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct Item(u32);
+
+trait Selector {
+    fn pick<'a>(&self, items: &'a [Item]) -> &'a Item;
+}
+
+#[automock]
+trait Parser {
+    fn token<'a>(&self, input: &'a str) -> &'a str;
+}
+
+mock! {
+    Foo {
+        fn first<'a>(&self, x: &'a u32, y: &u32) -> &'a u32;
+        fn get_mut<'a>(&self, items: &'a mut [Item]) -> &'a mut Item;
+        fn longer<'a>(&mut self, x: &'a str, y: &'a str) -> &'a str;
+        fn own<'a>(&self, x: &'a u32) -> &u32;
+        fn spick<'a>(items: &'a [Item]) -> &'a Item;
+    }
+    impl Selector for Foo {
+        fn pick<'a>(&self, items: &'a [Item]) -> &'a Item;
+    }
+}
+
+#[test]
+#[should_panic(expected =
+    "MockFoo::first: Expectation(<anything>) Can only return default values")]
+fn default() {
+    let mut mock = MockFoo::new();
+    mock.expect_first();
+    mock.first(&5, &6);
+}
+
+#[test]
+fn elided_arg() {
+    let mut mock = MockFoo::new();
+    mock.expect_first()
+        .returning(|x, _| x);
+    let x = 5;
+    assert_eq!(5, *mock.first(&x, &6));
+}
+
+#[test]
+fn mutable() {
+    let mut mock = MockFoo::new();
+    mock.expect_get_mut()
+        .returning(|items| &mut items[1]);
+    let mut items = [Item(1), Item(2)];
+    mock.get_mut(&mut items).0 = 42;
+    assert_eq!([Item(1), Item(42)], items);
+}
+
+#[test]
+fn mut_self() {
+    let mut mock = MockFoo::new();
+    mock.expect_longer()
+        .returning(|x, y| if x.len() >= y.len() { x } else { y });
+    assert_eq!("abc", mock.longer("ab", "abc"));
+}
+
+#[test]
+fn parser() {
+    let mut mock = MockParser::new();
+    mock.expect_token()
+        .returning(|input| input.split(' ').next().unwrap());
+    let input = String::from("hello world");
+    assert_eq!("hello", mock.token(&input));
+}
+
+#[test]
+fn return_once() {
+    let mut mock = MockFoo::new();
+    mock.expect_first()
+        .return_once(|x, _| x);
+    assert_eq!(5, *mock.first(&5, &6));
+}
+
+#[test]
+fn returning_st() {
+    let mut mock = MockFoo::new();
+    let index = std::rc::Rc::new(1);
+    mock.expect_pick()
+        .returning_st(move |items| &items[*index]);
+    let items = [Item(1), Item(2)];
+    assert_eq!(&Item(2), mock.pick(&items));
+}
+
+#[test]
+fn selector() {
+    let mut mock = MockFoo::new();
+    mock.expect_pick()
+        .returning(|items| &items[0]);
+    let items = vec![Item(1), Item(2)];
+    assert_eq!(&items[0], mock.pick(&items));
+}
+
+/// Methods that return a reference borrowed from `self` still return a value
+/// owned by the Expectation
+#[test]
+fn self_lifetime() {
+    let mut mock = MockFoo::new();
+    mock.expect_own()
+        .return_const(42u32);
+    assert_eq!(42, *mock.own(&5));
+}
+
+#[test]
+fn static_method() {
+    let ctx = MockFoo::spick_context();
+    ctx.expect()
+        .returning(|items| items.last().unwrap());
+    let items = [Item(1), Item(2)];
+    assert_eq!(&Item(2), MockFoo::spick(&items));
+}
+
+#[test]
+fn with() {
+    let mut mock = MockFoo::new();
+    mock.expect_pick()
+        .withf(|items| items.len() == 1)
+        .returning(|items| &items[0]);
+    mock.expect_pick()
+        .returning(|items| &items[1]);
+    assert_eq!(&Item(1), mock.pick(&[Item(1)]));
+    assert_eq!(&Item(3), mock.pick(&[Item(2), Item(3)]));
+}
@@ -11,9 +11,6 @@ mock! {
 }
 
 #[test]
-#[cfg_attr(not(feature = "nightly"),
-    should_panic(expected = "MockFoo::foo: Expectation(<anything>) Returning default values requires"))]
-#[cfg_attr(not(feature = "nightly"), allow(unused_must_use))]
 fn return_default() {
     let mut mock = MockFoo::new();
     mock.expect_foo();
@@ -47,10 +44,7 @@ mod sequence {
     use super::*;
 
     #[test]
-    #[cfg_attr(feature = "nightly",
-        should_panic(expected = "MockFoo::foo(4): Method sequence violation"))]
-    #[cfg_attr(not(feature = "nightly"),
-        should_panic(expected = "MockFoo::foo(?): Method sequence violation"))]
+    #[should_panic(expected = "MockFoo::foo(4): Method sequence violation")]
     fn fail() {
         let mut seq = Sequence::new();
         let mut mock = MockFoo::new();
@@ -3,11 +3,14 @@
 #![deny(warnings)]
 
 use mockall::*;
+use std::rc::Rc;
 
 mock! {
     Foo {
         fn foo(&self, x: i32) -> &u32;
         fn bar(&self) -> &u32;
+        fn lookup(&self, key: &str) -> &u32;
+        fn name(&self, x: u32) -> &str;
     }
 }
 
@@ -42,9 +45,6 @@ fn return_const() {
 }
 
 #[test]
-#[cfg_attr(not(feature = "nightly"),
-    should_panic(expected = "MockFoo::foo: Expectation(<anything>) Returning default values requires"))]
-#[cfg_attr(not(feature = "nightly"), allow(unused_must_use))]
 fn return_default() {
     let mut mock = MockFoo::new();
     mock.expect_foo();
@@ -52,25 +52,79 @@ fn return_default() {
     assert_eq!(u32::default(), *r);
 }
 
+mod returning {
+    use super::*;
+
+    #[test]
+    fn argument_dependent() {
+        let mut mock = MockFoo::new();
+        mock.expect_foo()
+            .returning(|x| x as u32 * 2);
+        assert_eq!(4, *mock.foo(2));
+        assert_eq!(6, *mock.foo(3));
+    }
+
+    /// References returned by earlier calls remain valid
+    #[test]
+    fn many_borrows() {
+        let mut mock = MockFoo::new();
+        mock.expect_foo()
+            .returning(|x| x as u32);
+        let refs = (0..100).map(|x| mock.foo(x)).collect::<Vec<_>>();
+        for (i, r) in refs.into_iter().enumerate() {
+            assert_eq!(i as u32, *r);
+        }
+    }
+
+    #[test]
+    fn reference_argument() {
+        let mut mock = MockFoo::new();
+        mock.expect_lookup()
+            .returning(|key| key.len() as u32);
+        assert_eq!(5, *mock.lookup("hello"));
+    }
+
+    #[test]
+    fn st() {
+        let factor = Rc::new(3);
+        let mut mock = MockFoo::new();
+        mock.expect_foo()
+            .returning_st(move |x| x as u32 * *factor);
+        assert_eq!(6, *mock.foo(2));
+    }
+
+    #[test]
+    fn str() {
+        let mut mock = MockFoo::new();
+        mock.expect_name()
+            .returning(|x| format!("name{}", x));
+        assert_eq!("name1", mock.name(1));
+        assert_eq!("name2", mock.name(2));
+    }
+}
+
 mod sequence {
     use super::*;
 
     #[test]
-    #[should_panic(expected = "exact call count")]
-    fn ambiguous() {
+    fn range() {
         let mut seq = Sequence::new();
         let mut mock = MockFoo::new();
         mock.expect_foo()
             .times(1..3)
+            .return_const(0)
+            .in_sequence(&mut seq);
+        mock.expect_bar()
+            .times(1)
+            .return_const(0)
             .in_sequence(&mut seq);
         mock.foo(4);
+        mock.foo(4);
+        mock.bar();
     }
 
     #[test]
-    #[cfg_attr(feature = "nightly",
-        should_panic(expected = "MockFoo::foo(4): Method sequence violation"))]
-    #[cfg_attr(not(feature = "nightly"),
-        should_panic(expected = "MockFoo::foo(?): Method sequence violation"))]
+    #[should_panic(expected = "MockFoo::foo(4): Method sequence violation")]
     fn fail() {
         let mut seq = Sequence::new();
         let mut mock = MockFoo::new();
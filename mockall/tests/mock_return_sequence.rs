@@ -0,0 +1,228 @@
+// vim: tw=80
+//! An expectation can return a scripted sequence of values
+#![deny(warnings)]
+
+use mockall::*;
+use std::{
+    iter,
+    sync::{Arc, atomic::{AtomicUsize, Ordering}}
+};
+
+#[derive(Debug, Eq, PartialEq)]
+struct NonClone(u32);
+
+mock! {
+    Foo {
+        fn foo(&self, x: u32) -> u32;
+        fn gen<T: 'static>(&self) -> T;
+        fn non_clone(&self) -> NonClone;
+        fn refer(&self) -> &u32;
+        fn res(&self) -> Result<u32, NonClone>;
+    }
+}
+
+#[test]
+fn fall_through() {
+    let mut mock = MockFoo::new();
+    mock.expect_foo()
+        .return_sequence(vec![1, 2])
+        .when_exhausted(Exhausted::FallThrough);
+    mock.expect_foo()
+        .return_const(99u32);
+    assert_eq!(1, mock.foo(0));
+    assert_eq!(2, mock.foo(0));
+    assert_eq!(99, mock.foo(0));
+    assert_eq!(99, mock.foo(0));
+}
+
+#[test]
+fn generic() {
+    let mut mock = MockFoo::new();
+    mock.expect_gen::<i16>()
+        .return_sequence(vec![-1, -2]);
+    assert_eq!(-1, mock.gen::<i16>());
+    assert_eq!(-2, mock.gen::<i16>());
+}
+
+#[test]
+fn iter() {
+    let mut mock = MockFoo::new();
+    mock.expect_foo()
+        .returning_iter((1..).step_by(2));
+    assert_eq!(1, mock.foo(0));
+    assert_eq!(3, mock.foo(0));
+    assert_eq!(5, mock.foo(0));
+}
+
+/// The iterator is only advanced when the method is called
+#[test]
+fn iter_lazy() {
+    let pulled = Arc::new(AtomicUsize::new(0));
+    let pulled2 = pulled.clone();
+    let mut values = vec![1u32, 2].into_iter();
+    let mut mock = MockFoo::new();
+    mock.expect_foo()
+        .returning_iter(iter::from_fn(move || {
+            pulled2.fetch_add(1, Ordering::Relaxed);
+            values.next()
+        })).when_exhausted(Exhausted::RepeatLast);
+    assert_eq!(0, pulled.load(Ordering::Relaxed));
+    assert_eq!(1, mock.foo(0));
+    assert_eq!(1, pulled.load(Ordering::Relaxed));
+    assert_eq!(2, mock.foo(0));
+    assert_eq!(2, pulled.load(Ordering::Relaxed));
+    assert_eq!(2, mock.foo(0));
+    assert_eq!(2, mock.foo(0));
+    assert_eq!(3, pulled.load(Ordering::Relaxed));
+}
+
+#[test]
+fn non_clone() {
+    let mut mock = MockFoo::new();
+    mock.expect_non_clone()
+        .return_sequence(vec![NonClone(1), NonClone(2)]);
+    assert_eq!(NonClone(1), mock.non_clone());
+    assert_eq!(NonClone(2), mock.non_clone());
+}
+
+#[test]
+#[should_panic(expected =
+    "MockFoo::foo: Expectation(<anything>) ran out of scripted return values")]
+fn panic() {
+    let mut mock = MockFoo::new();
+    mock.expect_foo()
+        .return_sequence(vec![1]);
+    mock.foo(0);
+    mock.foo(0);
+}
+
+/// Non-Clone values can't be repeated
+#[test]
+#[should_panic(expected =
+    "can only repeat the last one for types that impl Clone and Send")]
+fn repeat_last_non_clone() {
+    let mut mock = MockFoo::new();
+    mock.expect_non_clone()
+        .return_sequence(vec![NonClone(1)])
+        .when_exhausted(Exhausted::RepeatLast);
+    mock.non_clone();
+    mock.non_clone();
+}
+
+#[test]
+fn repeat_last() {
+    let mut mock = MockFoo::new();
+    mock.expect_foo()
+        .return_sequence(vec![1, 2])
+        .when_exhausted(Exhausted::RepeatLast);
+    assert_eq!(1, mock.foo(0));
+    assert_eq!(2, mock.foo(0));
+    assert_eq!(2, mock.foo(0));
+    assert_eq!(2, mock.foo(0));
+}
+
+#[test]
+fn reference() {
+    let mut mock = MockFoo::new();
+    mock.expect_refer()
+        .return_sequence(vec![1, 2]);
+    assert_eq!(1, *mock.refer());
+    assert_eq!(2, *mock.refer());
+}
+
+#[test]
+fn reference_fall_through() {
+    let mut mock = MockFoo::new();
+    mock.expect_refer()
+        .return_sequence(vec![1])
+        .when_exhausted(Exhausted::FallThrough);
+    mock.expect_refer()
+        .return_const(99);
+    assert_eq!(1, *mock.refer());
+    assert_eq!(99, *mock.refer());
+}
+
+#[test]
+#[should_panic(expected =
+    "MockFoo::refer: Expectation(<anything>) ran out of scripted return values")]
+fn reference_panic() {
+    let mut mock = MockFoo::new();
+    mock.expect_refer()
+        .return_sequence(vec![1]);
+    mock.refer();
+    mock.refer();
+}
+
+#[test]
+fn reference_repeat_last() {
+    let mut mock = MockFoo::new();
+    mock.expect_refer()
+        .return_sequence(vec![1, 2])
+        .when_exhausted(Exhausted::RepeatLast);
+    assert_eq!(1, *mock.refer());
+    assert_eq!(2, *mock.refer());
+    assert_eq!(2, *mock.refer());
+}
+
+/// A return setter that replaces a script also replaces its exhaustion
+#[test]
+fn reference_replaced() {
+    let mut mock = MockFoo::new();
+    mock.expect_refer()
+        .return_sequence(vec![])
+        .when_exhausted(Exhausted::FallThrough)
+        .return_const(5);
+    mock.expect_refer()
+        .return_const(99);
+    assert_eq!(5, *mock.refer());
+}
+
+/// A script that replaces an exhausted one starts afresh
+#[test]
+fn reference_rescripted() {
+    let mut mock = MockFoo::new();
+    mock.expect_refer()
+        .return_sequence(vec![])
+        .when_exhausted(Exhausted::FallThrough)
+        .return_sequence(vec![7]);
+    mock.expect_refer()
+        .return_const(99);
+    assert_eq!(7, *mock.refer());
+}
+
+/// A return setter that replaces a script also replaces its exhaustion
+#[test]
+fn replaced() {
+    let mut mock = MockFoo::new();
+    mock.expect_foo()
+        .return_sequence(Vec::<u32>::new())
+        .when_exhausted(Exhausted::FallThrough)
+        .returning(|x| x + 5);
+    mock.expect_foo()
+        .return_const(99u32);
+    assert_eq!(5, mock.foo(0));
+    assert_eq!(6, mock.foo(1));
+}
+
+#[test]
+fn result() {
+    let mut mock = MockFoo::new();
+    mock.expect_res()
+        .return_sequence(vec![Ok(1), Err(NonClone(2)), Ok(3)]);
+    assert_eq!(Ok(1), mock.res());
+    assert_eq!(Err(NonClone(2)), mock.res());
+    assert_eq!(Ok(3), mock.res());
+}
+
+/// A single exhausted expectation still handles calls, so that they fail with
+/// a helpful message
+#[test]
+#[should_panic(expected = "ran out of scripted return values")]
+fn sole_fall_through() {
+    let mut mock = MockFoo::new();
+    mock.expect_foo()
+        .return_sequence(vec![1])
+        .when_exhausted(Exhausted::FallThrough);
+    mock.foo(0);
+    mock.foo(0);
+}
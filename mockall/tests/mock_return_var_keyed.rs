@@ -0,0 +1,127 @@
+// vim: tw=80
+//! A method that returns a mutable reference can return a different variable
+//! for each key computed from its arguments
+#![deny(warnings)]
+
+use mockall::*;
+
+trait Registry {
+    fn slot(&mut self, name: &str) -> &mut Vec<u32>;
+}
+
+mock! {
+    Foo {
+        fn entry(&mut self, k: u32) -> &mut String;
+        fn gen<T: 'static>(&mut self, k: u32) -> &mut T;
+        fn pair(&mut self, x: u32, y: u32) -> &mut u32;
+    }
+    impl Registry for Foo {
+        fn slot(&mut self, name: &str) -> &mut Vec<u32>;
+    }
+}
+
+#[test]
+fn expectation_var() {
+    let mut mock = MockFoo::new();
+    let e = mock.expect_entry()
+        .return_var_keyed(|k| k, |_| String::new());
+    assert!(e.var(&1u32).is_none());
+}
+
+#[test]
+fn fallback() {
+    let mut mock = MockFoo::new();
+    mock.fallback_entry()
+        .return_var_keyed(|k| k, |_| String::new());
+    mock.entry(3).push('x');
+    assert_eq!("x", mock.var_entry(&3u32).unwrap());
+}
+
+#[test]
+fn generic_method() {
+    let mut mock = MockFoo::new();
+    mock.expect_gen::<i16>()
+        .return_var_keyed(|k| k, |_| 0i16);
+    *mock.gen::<i16>(1) = -1;
+    *mock.gen::<i16>(2) = -2;
+    assert_eq!(Some(&-1), mock.var_gen::<i16, u32>(&1));
+    assert_eq!(Some(&-2), mock.var_gen::<i16, u32>(&2));
+    assert_eq!(None, mock.var_gen::<i64, u32>(&1));
+}
+
+#[test]
+fn init_from_key() {
+    let mut mock = MockFoo::new();
+    mock.expect_entry()
+        .return_var_keyed(|k| k, |k| format!("#{}", k));
+    assert_eq!("#1", mock.entry(1));
+    assert_eq!("#2", mock.entry(2));
+}
+
+#[test]
+fn keys() {
+    let mut mock = MockFoo::new();
+    mock.expect_entry()
+        .return_var_keyed(|k| k, |_| String::new());
+    mock.entry(1).push_str("one");
+    mock.entry(2).push_str("two");
+    mock.entry(1).push_str("uno");
+    assert_eq!("oneuno", mock.var_entry(&1u32).unwrap());
+    assert_eq!("two", mock.var_entry(&2u32).unwrap());
+    assert_eq!(None, mock.var_entry(&3u32));
+}
+
+#[test]
+fn key_from_several_args() {
+    let mut mock = MockFoo::new();
+    mock.expect_pair()
+        .return_var_keyed(|x, _| x % 2, |_| 0);
+    *mock.pair(1, 10) += 10;
+    *mock.pair(3, 20) += 20;
+    *mock.pair(2, 40) += 40;
+    assert_eq!(Some(&30), mock.var_pair(&1u32));
+    assert_eq!(Some(&40), mock.var_pair(&0u32));
+}
+
+/// A key of the wrong type could never be found, so it's an error
+#[test]
+#[should_panic(expected = "MockFoo::entry: keys are u32, not u64")]
+fn key_type() {
+    let mut mock = MockFoo::new();
+    mock.expect_entry()
+        .return_var_keyed(|k| k, |_| String::from("x"));
+    mock.entry(1);
+    mock.var_entry(&1u64);
+}
+
+#[test]
+fn no_vars() {
+    let mut mock = MockFoo::new();
+    mock.expect_entry()
+        .return_var(String::new());
+    mock.entry(1);
+    assert_eq!(None, mock.var_entry(&1u32));
+}
+
+#[test]
+fn reference_arg() {
+    let mut mock = MockFoo::new();
+    mock.expect_slot()
+        .return_var_keyed(|name| name.to_owned(), |_| Vec::new());
+    mock.slot("a").push(1);
+    mock.slot("b").push(2);
+    mock.slot("a").push(3);
+    assert_eq!(Some(&vec![1, 3]), mock.var_slot(&String::from("a")));
+    assert_eq!(Some(&vec![2]), mock.var_slot(&String::from("b")));
+}
+
+#[test]
+fn times() {
+    let mut mock = MockFoo::new();
+    mock.expect_entry()
+        .times(2)
+        .return_var_keyed(|k| k, |_| String::new());
+    mock.entry(1);
+    mock.entry(2);
+    mock.checkpoint();
+}
@@ -0,0 +1,189 @@
+// vim: tw=80
+//! Spy mocks forward unmatched calls to a real object
+#![deny(warnings)]
+
+use mockall::*;
+
+#[automock]
+trait Foo {
+    fn foo(&self, x: u32) -> u32;
+    fn incr(&mut self) -> u32;
+    fn name(&self, prefix: &str) -> String;
+}
+
+trait Bar<T: 'static> {
+    fn bar(&self, t: T) -> T;
+}
+
+#[automock]
+trait Gen {
+    fn gen<T: 'static>(&self, t: T) -> T;
+}
+
+/// Resolves `spy` for mocks that have no inherent `spy` method
+trait NoSpy {
+    fn spy<R>(_real: R) -> &'static str {
+        "no spy"
+    }
+}
+
+impl NoSpy for MockGen {}
+
+mock! {
+    Baz {
+        fn inherent(&self) -> u32;
+    }
+    impl Foo for Baz {
+        fn foo(&self, x: u32) -> u32;
+        fn incr(&mut self) -> u32;
+        fn name(&self, prefix: &str) -> String;
+    }
+    impl Bar<u16> for Baz {
+        fn bar(&self, t: u16) -> u16;
+    }
+}
+
+#[derive(Default)]
+struct Real {
+    count: u32
+}
+
+impl Foo for Real {
+    fn foo(&self, x: u32) -> u32 {
+        assert!(x != 13, "unlucky");
+        x * 2
+    }
+
+    fn incr(&mut self) -> u32 {
+        self.count += 1;
+        self.count
+    }
+
+    fn name(&self, prefix: &str) -> String {
+        format!("{}real", prefix)
+    }
+}
+
+impl Bar<u16> for Real {
+    fn bar(&self, t: u16) -> u16 {
+        t + 1
+    }
+}
+
+/// Checkpointing discards expectations, but keeps forwarding calls
+#[test]
+fn checkpoint() {
+    let mut mock = MockFoo::spy(Real::default());
+    mock.expect_foo()
+        .return_const(0u32);
+    mock.checkpoint();
+    assert_eq!(8, mock.foo(4));
+}
+
+#[test]
+fn forward() {
+    let mock = MockFoo::spy(Real::default());
+    assert_eq!(8, mock.foo(4));
+    assert_eq!("my real", mock.name("my "));
+}
+
+/// Methods that take `&mut self` forward to the same real object each time
+#[test]
+fn forward_mut() {
+    let mut mock = MockFoo::spy(Real::default());
+    assert_eq!(1, mock.incr());
+    assert_eq!(2, mock.incr());
+}
+
+/// A mock that couldn't forward all of its trait methods has no spy
+#[test]
+fn generic() {
+    assert_eq!("no spy", MockGen::spy(Real::default()));
+    let mut mock = MockGen::new();
+    mock.expect_gen::<u32>()
+        .returning(|t| t + 1);
+    assert_eq!(5, mock.gen(4u32));
+}
+
+/// Inherent methods can't be forwarded
+#[test]
+#[should_panic(expected = "MockBaz::inherent(): No matching expectation found")]
+fn inherent() {
+    let mock = MockBaz::spy(Real::default());
+    mock.inherent();
+}
+
+#[test]
+fn multiple_traits() {
+    let mut mock = MockBaz::spy(Real::default());
+    mock.expect_inherent()
+        .return_const(7u32);
+    assert_eq!(8, mock.foo(4));
+    assert_eq!(5, mock.bar(4));
+    assert_eq!(7, mock.inherent());
+}
+
+#[test]
+fn override_result() {
+    let mut mock = MockFoo::spy(Real::default());
+    mock.expect_foo()
+        .with(predicate::eq(1))
+        .return_const(100u32);
+    assert_eq!(100, mock.foo(1));
+    assert_eq!(4, mock.foo(2));
+}
+
+/// A forwarded call that panics doesn't break later ones
+#[test]
+fn poisoned() {
+    let mock = MockFoo::spy(Real::default());
+    let r = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        mock.foo(13);
+    }));
+    assert!(r.is_err());
+    assert_eq!(8, mock.foo(4));
+}
+
+#[test]
+fn recorded() {
+    let mut mock = MockFoo::spy(Real::default());
+    mock.expect_foo()
+        .with(predicate::eq(1))
+        .return_const(100u32);
+    mock.foo(1);
+    mock.foo(2);
+    let calls = mock.calls_foo();
+    assert_eq!(2, calls.len());
+    assert_eq!(Some(0), calls[0].expectation());
+    assert_eq!(None, calls[1].expectation());
+    assert_eq!(calls[1].args(), ["2"]);
+}
+
+#[test]
+fn sequence() {
+    let mut seq = Sequence::new();
+    let mut mock = MockFoo::spy(Real::default());
+    mock.expect_incr()
+        .times(1)
+        .in_sequence(&mut seq)
+        .return_const(10u32);
+    mock.expect_foo()
+        .with(predicate::eq(1))
+        .times(1)
+        .in_sequence(&mut seq)
+        .return_const(0u32);
+    assert_eq!(10, mock.incr());
+    assert_eq!(0, mock.foo(1));
+    assert_eq!(6, mock.foo(3));
+}
+
+#[test]
+#[should_panic(expected =
+    "MockFoo::foo: Expectation(<anything>) called 0 time(s) which is fewer than expected 1")]
+fn times() {
+    let mut mock = MockFoo::spy(Real::default());
+    mock.expect_foo()
+        .times(1)
+        .return_const(0u32);
+    mock.name("x");
+}
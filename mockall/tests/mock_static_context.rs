@@ -0,0 +1,106 @@
+// vim: tw=80
+//! Only one Context may exist at a time for each static method
+#![deny(warnings)]
+
+use mockall::*;
+use std::{panic, thread};
+
+mock! {
+    Foo {
+        fn foo(x: u32) -> u32;
+        fn bar(x: u32) -> u32;
+        fn baz(x: u32) -> u32;
+        fn bean(x: u32) -> u32;
+        fn boom(x: u32) -> u32;
+        fn left(x: u32) -> u32;
+        fn right(x: u32) -> u32;
+    }
+}
+
+#[test]
+fn after_drop() {
+    {
+        let ctx = MockFoo::baz_context();
+        ctx.expect()
+            .return_const(1u32);
+        assert_eq!(1, MockFoo::baz(0));
+    }
+    let ctx = MockFoo::baz_context();
+    ctx.expect()
+        .return_const(2u32);
+    assert_eq!(2, MockFoo::baz(0));
+}
+
+/// A Context whose drop panics should still release the method
+#[test]
+fn after_failed_drop() {
+    panic::catch_unwind(|| {
+        let ctx = MockFoo::bean_context();
+        ctx.expect()
+            .times(1)
+            .return_const(1u32);
+    }).unwrap_err();
+    let ctx = MockFoo::bean_context();
+    ctx.expect()
+        .return_const(2u32);
+    assert_eq!(2, MockFoo::bean(0));
+}
+
+/// Locked Contexts serialize tests that share a static method
+#[test]
+fn locked() {
+    let threads = (0..8u32).map(|i| thread::spawn(move || {
+        let ctx = MockFoo::foo_context_locked();
+        ctx.expect()
+            .return_const(i);
+        thread::yield_now();
+        assert_eq!(i, MockFoo::foo(0));
+    })).collect::<Vec<_>>();
+    for t in threads {
+        t.join().unwrap();
+    }
+}
+
+/// Threads that lock several methods' Contexts, in any order, take turns
+/// instead of deadlocking
+#[test]
+fn locked_two_methods() {
+    let threads = (0..8u32).map(|i| thread::spawn(move || {
+        let (ctx0, ctx1) = if i % 2 == 0 {
+            let ctx0 = MockFoo::left_context_locked();
+            thread::yield_now();
+            (ctx0, MockFoo::right_context_locked())
+        } else {
+            let ctx1 = MockFoo::right_context_locked();
+            thread::yield_now();
+            (MockFoo::left_context_locked(), ctx1)
+        };
+        ctx0.expect()
+            .return_const(i);
+        ctx1.expect()
+            .return_const(i + 1);
+        thread::yield_now();
+        assert_eq!(i, MockFoo::left(0));
+        assert_eq!(i + 1, MockFoo::right(0));
+    })).collect::<Vec<_>>();
+    for t in threads {
+        t.join().unwrap();
+    }
+}
+
+/// Waiting for a Context held by the same thread would deadlock
+#[test]
+#[should_panic(expected =
+    "MockFoo::boom: This thread already holds a Context for this method, so MockFoo::boom_context_locked would wait for it forever.")]
+fn locked_reentrant() {
+    let _ctx0 = MockFoo::boom_context();
+    let _ctx1 = MockFoo::boom_context_locked();
+}
+
+#[test]
+#[should_panic(expected =
+    "MockFoo::bar: A Context for this method already exists")]
+fn second_context() {
+    let _ctx0 = MockFoo::bar_context();
+    let _ctx1 = MockFoo::bar_context();
+}
@@ -0,0 +1,48 @@
+// vim: tw=80
+//! A panic in one static method call shouldn't break later uses of the method
+#![deny(warnings)]
+
+use mockall::*;
+use std::panic;
+
+mock! {
+    Foo {
+        fn foo(x: u32) -> u32;
+        fn bar(x: u32) -> u32;
+    }
+}
+
+/// A call count violation panics while the expectations are locked
+#[test]
+fn after_violation() {
+    {
+        let ctx = MockFoo::foo_context();
+        ctx.expect()
+            .times(1)
+            .return_const(0u32);
+        MockFoo::foo(1);
+        panic::catch_unwind(|| MockFoo::foo(2)).unwrap_err();
+    }
+    let ctx = MockFoo::foo_context();
+    ctx.expect()
+        .return_const(5u32);
+    assert_eq!(5, MockFoo::foo(3));
+    assert_eq!(1, ctx.calls().len());
+}
+
+/// Expectations should be cleared even if the Context drops during a panic
+#[test]
+fn context_dropped_while_panicking() {
+    panic::catch_unwind(|| {
+        let ctx = MockFoo::bar_context();
+        ctx.expect()
+            .return_const(1u32);
+        panic!("test failure");
+    }).unwrap_err();
+    let ctx = MockFoo::bar_context();
+    ctx.expect()
+        .with(predicate::eq(6))
+        .return_const(6u32);
+    assert_eq!(6, MockFoo::bar(6));
+    assert!(panic::catch_unwind(|| MockFoo::bar(7)).is_err());
+}
@@ -23,6 +23,108 @@ mock!{
     }
 }
 
+mod after {
+    use super::*;
+
+    #[test]
+    #[should_panic(expected =
+        "MockFoo::baz(): Prerequisite violation.  MockFoo::bar: Expectation(var == 2) (set at ")]
+    fn fail() {
+        let mut mock = MockFoo::new();
+        let bar1 = mock.expect_bar()
+            .with(predicate::eq(1))
+            .times(1)
+            .returning(|_| ())
+            .prerequisite();
+        let bar2 = mock.expect_bar()
+            .with(predicate::eq(2))
+            .times(1)
+            .returning(|_| ())
+            .prerequisite();
+        mock.expect_baz()
+            .returning(|| ())
+            .after(&[&bar1, &bar2]);
+
+        mock.bar(1);
+        mock.baz();
+    }
+
+    #[test]
+    fn ok() {
+        let mut mock = MockFoo::new();
+        let bar1 = mock.expect_bar()
+            .with(predicate::eq(1))
+            .times(1)
+            .returning(|_| ())
+            .prerequisite();
+        let bar2 = mock.expect_bar()
+            .with(predicate::eq(2))
+            .times(1)
+            .returning(|_| ())
+            .prerequisite();
+        mock.expect_baz()
+            .times(1)
+            .returning(|| ())
+            .after(&[&bar1, &bar2]);
+
+        mock.bar(2);
+        mock.bar(1);
+        mock.baz();
+    }
+
+    /// A prerequisite with a range of call counts is satisfied once it reaches
+    /// its minimum
+    #[test]
+    fn range() {
+        let mut mock = MockFoo::new();
+        let bar = mock.expect_bar()
+            .times(2..5)
+            .returning(|_| ())
+            .prerequisite();
+        mock.expect_baz()
+            .returning(|| ())
+            .after(&[&bar]);
+
+        mock.bar(1);
+        mock.bar(1);
+        mock.baz();
+        mock.bar(1);
+    }
+
+    /// A prerequisite may be shared across mock objects
+    #[test]
+    fn two_objects() {
+        let mut mock0 = MockFoo::new();
+        let mut mock1 = MockFoo::new();
+        let bar = mock0.expect_bar()
+            .times(1)
+            .returning(|_| ())
+            .prerequisite();
+        mock1.expect_baz()
+            .times(1)
+            .returning(|| ())
+            .after(&[&bar]);
+
+        mock0.bar(0);
+        mock1.baz();
+    }
+
+    /// An expectation whose minimum call count is zero is always satisfied
+    #[test]
+    fn zero() {
+        let mut mock = MockFoo::new();
+        let bar = mock.expect_bar()
+            .times(..2)
+            .returning(|_| ())
+            .prerequisite();
+        mock.expect_baz()
+            .returning(|| ())
+            .after(&[&bar]);
+
+        mock.baz();
+    }
+}
+
 mod checkpoint {
     use super::*;
 
@@ -64,12 +166,7 @@ mod checkpoint {
     }
 
     #[test]
-    #[cfg_attr(feature = "nightly", should_panic(
-            expected = "MockFoo::foo(0): No matching expectation found"
-    ))]
-    #[cfg_attr(not(feature = "nightly"), should_panic(
-            expected = "MockFoo::foo(?): No matching expectation found"
-    ))]
+    #[should_panic(expected = "MockFoo::foo(0): No matching expectation found")]
     fn removes_old_expectations() {
         let mut mock = MockFoo::new();
         mock.expect_foo()
@@ -123,12 +220,7 @@ mod r#match {
     }
 
     #[test]
-    #[cfg_attr(feature = "nightly", should_panic(
-            expected = "MockFoo::bar(5): No matching expectation found"
-    ))]
-    #[cfg_attr(not(feature = "nightly"), should_panic(
-            expected = "MockFoo::bar(?): No matching expectation found"
-    ))]
+    #[should_panic(expected = "MockFoo::bar(5): No matching expectation found")]
     fn with_no_matches() {
         let mut mock = MockFoo::new();
         mock.expect_bar()
@@ -137,6 +229,46 @@ mod r#match {
         mock.bar(5);
     }
 
+    /// The panic message should explain why each expectation was rejected,
+    /// and say where each was created
+    #[test]
+    fn with_no_matches_explanation() {
+        let mut mock = MockFoo::new();
+        let line0 = line!() + 1;
+        mock.expect_bar()
+            .with(predicate::eq(4))
+            .return_const(());
+        let line1 = line!() + 1;
+        mock.expect_bar()
+            .withf(|x: &u32| *x == 6)
+            .return_const(());
+        let line2 = line!() + 1;
+        mock.expect_bar()
+            .times(1)
+            .return_const(());
+        mock.bar(5);
+        let r = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            mock.bar(5);
+        }));
+        let msg = r.unwrap_err().downcast::<String>().unwrap();
+        let expected = format!("No matching expectation found
+    0. Expectation(var == 4) (set at {file}:{}:14) did not match: some arguments failed their predicates
+        x: var == 4
+            └── var: 5
+    1. Expectation(<function>) (set at {file}:{}:14) did not match: the matching function returned false
+    2. Expectation(<anything>) (set at {file}:{}:14) matched, but it was already called 1 time(s), the most allowed",
+            line0, line1, line2, file = file!());
+        assert!(msg.ends_with(&expected), "{}", msg);
+    }
+
+    #[test]
+    #[should_panic(expected =
+        "No matching expectation found\n    No expectations have been set")]
+    fn without_expectations() {
+        let mock = MockFoo::new();
+        mock.baz();
+    }
+
     #[test]
     fn with_ok() {
         let mut mock = MockFoo::new();
@@ -156,12 +288,7 @@ mod r#match {
     }
 
     #[test]
-    #[cfg_attr(feature = "nightly", should_panic(
-            expected = "MockFoo::bar(5): No matching expectation found"
-    ))]
-    #[cfg_attr(not(feature = "nightly"), should_panic(
-            expected = "MockFoo::bar(?): No matching expectation found"
-    ))]
+    #[should_panic(expected = "MockFoo::bar(5): No matching expectation found")]
     fn withf_no_matches() {
         let mut mock = MockFoo::new();
         mock.expect_bar()
@@ -172,6 +299,68 @@ mod r#match {
 
 }
 
+mod named {
+    use super::*;
+
+    #[test]
+    fn call_history() {
+        let mut mock = MockFoo::new();
+        mock.expect_bar()
+            .withf(|x: &u32| *x == 1)
+            .named("bar one")
+            .return_const(());
+        mock.expect_bar()
+            .with(predicate::eq(2))
+            .return_const(());
+        mock.bar(1);
+        mock.bar(2);
+        let calls = mock.calls_bar();
+        assert_eq!(Some("bar one"), calls[0].description());
+        assert_eq!(Some("var == 2"), calls[1].description());
+    }
+
+    #[test]
+    fn debug() {
+        let mut mock = MockFoo::new();
+        let e = mock.expect_baz()
+            .named("the baz")
+            .return_const(());
+        let s = format!("{:?}", e);
+        assert!(s.starts_with("MockFoo::baz: Expectation(the baz) (set at "),
+            "{}", s);
+    }
+
+    #[test]
+    #[should_panic(expected =
+        "MockFoo::bar: Expectation(bar one) called 0 time(s) which is fewer than expected 1")]
+    fn panic() {
+        let mut mock = MockFoo::new();
+        mock.expect_bar()
+            .withf(|x: &u32| *x == 1)
+            .times(1)
+            .named("bar one")
+            .return_const(());
+    }
+
+    #[test]
+    #[should_panic(expected =
+        "MockFoo::baz(): Method sequence violation.  MockFoo::bar: Expectation(bar first) (set at ")]
+    fn sequence() {
+        let mut seq = Sequence::new();
+        let mut mock = MockFoo::new();
+        mock.expect_bar()
+            .times(1)
+            .return_const(())
+            .in_sequence(&mut seq)
+            .named("bar first");
+        mock.expect_baz()
+            .times(1)
+            .return_const(())
+            .in_sequence(&mut seq);
+        mock.baz();
+    }
+}
+
 mod never {
     use super::*;
 
@@ -202,9 +391,6 @@ fn return_const() {
     assert_eq!(42, mock.foo(5));
 }
 
-#[cfg_attr(not(feature = "nightly"),
-    should_panic(expected = "MockFoo::foo: Expectation(<anything>) Returning default values requires"))]
-#[cfg_attr(not(feature = "nightly"), allow(unused_must_use))]
 #[test]
 fn return_default() {
     let mut mock = MockFoo::new();
@@ -225,14 +411,75 @@ mod sequence {
     use super::*;
 
     #[test]
-    #[should_panic(expected = "exact call count")]
-    fn ambiguous() {
+    #[should_panic(expected = "MockFoo::bar(0): Method sequence violation")]
+    fn range_fail() {
         let mut seq = Sequence::new();
         let mut mock = MockFoo::new();
         mock.expect_baz()
-            .times(1..3)
+            .times(2..4)
+            .returning(|| ())
             .in_sequence(&mut seq);
+        mock.expect_bar()
+            .times(1)
+            .returning(|_| ())
+            .in_sequence(&mut seq);
+
         mock.baz();
+        mock.bar(0);
+    }
+
+    #[test]
+    #[should_panic(expected = "MockFoo::baz(): Method sequence violation")]
+    fn range_backwards() {
+        let mut seq = Sequence::new();
+        let mut mock = MockFoo::new();
+        mock.expect_baz()
+            .times(1..4)
+            .returning(|| ())
+            .in_sequence(&mut seq);
+        mock.expect_bar()
+            .times(1)
+            .returning(|_| ())
+            .in_sequence(&mut seq);
+
+        mock.baz();
+        mock.bar(0);
+        mock.baz();
+    }
+
+    #[test]
+    fn range_ok() {
+        let mut seq = Sequence::new();
+        let mut mock = MockFoo::new();
+        mock.expect_baz()
+            .times(1..4)
+            .returning(|| ())
+            .in_sequence(&mut seq);
+        mock.expect_bar()
+            .times(1)
+            .returning(|_| ())
+            .in_sequence(&mut seq);
+
+        mock.baz();
+        mock.baz();
+        mock.bar(0);
+    }
+
+    /// An expectation whose minimum call count is zero may be skipped
+    #[test]
+    fn range_skip() {
+        let mut seq = Sequence::new();
+        let mut mock = MockFoo::new();
+        mock.expect_baz()
+            .times(..3)
+            .returning(|| ())
+            .in_sequence(&mut seq);
+        mock.expect_bar()
+            .times(1)
+            .returning(|_| ())
+            .in_sequence(&mut seq);
+
+        mock.bar(0);
     }
 
     #[test]
@@ -254,6 +501,130 @@ mod sequence {
         mock.bar(0);
     }
 
+    /// The sequence may not advance until every member of a group is
+    /// satisfied
+    #[test]
+    #[should_panic(expected = "MockFoo::baz(): Method sequence violation.  Group \"bars\" has outstanding members:\n    MockFoo::bar: Expectation(var == 1) (set at ")]
+    fn group_fail() {
+        let mut seq = Sequence::new();
+        let mut mock = MockFoo::new();
+        seq.begin_group("bars");
+        for i in 1..4 {
+            mock.expect_bar()
+                .with(predicate::eq(i))
+                .times(1)
+                .returning(|_| ())
+                .in_sequence(&mut seq);
+        }
+        seq.end_group();
+        mock.expect_baz()
+            .times(1)
+            .returning(|| ())
+            .in_sequence(&mut seq);
+
+        mock.bar(2);
+        mock.baz();
+    }
+
+    /// Members of a group may be called in any order
+    #[test]
+    fn group_ok() {
+        let mut seq = Sequence::new();
+        let mut mock = MockFoo::new();
+        mock.expect_foo()
+            .times(1)
+            .returning(|x| x)
+            .in_sequence(&mut seq);
+        seq.begin_group("bars");
+        for i in 1..4 {
+            mock.expect_bar()
+                .with(predicate::eq(i))
+                .times(1)
+                .returning(|_| ())
+                .in_sequence(&mut seq);
+        }
+        seq.end_group();
+        mock.expect_baz()
+            .times(1)
+            .returning(|| ())
+            .in_sequence(&mut seq);
+
+        mock.foo(0);
+        mock.bar(3);
+        mock.bar(1);
+        mock.bar(2);
+        mock.baz();
+    }
+
+    /// A group's members may not be called before the step preceding the group
+    #[test]
+    #[should_panic(expected =
+        "MockFoo::bar(1): Method sequence violation.  MockFoo::foo: Expectation(<anything>) (set at ")]
+    fn group_too_early() {
+        let mut seq = Sequence::new();
+        let mut mock = MockFoo::new();
+        mock.expect_foo()
+            .times(1)
+            .returning(|x| x)
+            .in_sequence(&mut seq);
+        seq.begin_group("bars");
+        mock.expect_bar()
+            .times(1)
+            .returning(|_| ())
+            .in_sequence(&mut seq);
+        seq.end_group();
+
+        mock.bar(1);
+    }
+
+    /// An expectation in two sequences must obey both of them
+    #[test]
+    #[should_panic(expected = "MockFoo::baz(): Method sequence violation")]
+    fn multiple_fail() {
+        let mut seq0 = Sequence::new();
+        let mut seq1 = Sequence::new();
+        let mut mock = MockFoo::new();
+        mock.expect_foo()
+            .times(1)
+            .returning(|x| x)
+            .in_sequence(&mut seq0)
+            .in_sequence(&mut seq1);
+        mock.expect_bar()
+            .times(1)
+            .returning(|_| ())
+            .in_sequence(&mut seq0);
+        mock.expect_baz()
+            .times(1)
+            .returning(|| ())
+            .in_sequence(&mut seq1);
+
+        mock.baz();
+    }
+
+    #[test]
+    fn multiple_ok() {
+        let mut seq0 = Sequence::new();
+        let mut seq1 = Sequence::new();
+        let mut mock = MockFoo::new();
+        mock.expect_foo()
+            .times(1)
+            .returning(|x| x)
+            .in_sequence(&mut seq0)
+            .in_sequence(&mut seq1);
+        mock.expect_bar()
+            .times(1)
+            .returning(|_| ())
+            .in_sequence(&mut seq0);
+        mock.expect_baz()
+            .times(1)
+            .returning(|| ())
+            .in_sequence(&mut seq1);
+
+        mock.foo(0);
+        mock.baz();
+        mock.bar(0);
+    }
+
     #[test]
     fn ok() {
         let mut seq = Sequence::new();
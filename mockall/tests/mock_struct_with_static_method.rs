@@ -17,6 +17,25 @@ lazy_static! {
     static ref BAR_MTX: Mutex<()> = Mutex::new(());
 }
 
+// Static methods' expectations may be prerequisites of others
+#[test]
+fn after() {
+    let _m = BAR_MTX.lock().unwrap();
+
+    let ctx = MockFoo::bar_context();
+    let bar1 = ctx.expect()
+        .with(predicate::eq(1))
+        .times(1)
+        .return_const(1u64)
+        .prerequisite();
+    ctx.expect()
+        .with(predicate::eq(2))
+        .return_const(2u64)
+        .after(&[&bar1]);
+    assert_eq!(1, MockFoo::bar(1));
+    assert_eq!(2, MockFoo::bar(2));
+}
+
 // Checkpointing the mock object should not checkpoint static methods
 #[test]
 fn checkpoint() {
@@ -46,12 +65,7 @@ fn ctx_checkpoint() {
 
 // Expectations should be cleared when a context object drops
 #[test]
-#[cfg_attr(feature = "nightly", should_panic(
-        expected = "MockFoo::bar3(42): No matching expectation found"
-))]
-#[cfg_attr(not(feature = "nightly"), should_panic(
-        expected = "MockFoo::bar3(?): No matching expectation found"
-))]
+#[should_panic(expected = "MockFoo::bar3(42): No matching expectation found")]
 fn ctx_hygiene() {
     {
         let ctx0 = MockFoo::bar3_context();
@@ -71,8 +85,6 @@ fn return_const() {
     assert_eq!(42, MockFoo::bar(41));
 }
 
-#[cfg_attr(not(feature = "nightly"), ignore)]
-#[cfg_attr(not(feature = "nightly"), allow(unused_must_use))]
 #[test]
 fn return_default() {
     let _m = BAR_MTX.lock().unwrap();
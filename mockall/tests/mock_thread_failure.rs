@@ -0,0 +1,73 @@
+// vim: tw=80
+//! Violations raised on other threads are re-raised on the mock's own thread
+#![deny(warnings)]
+
+use mockall::*;
+use std::{
+    sync::{Arc, Mutex},
+    thread
+};
+
+mock! {
+    Foo {
+        fn foo(&self, x: u32) -> u32;
+        fn bar(x: u32) -> u32;
+    }
+}
+
+/// Calls from another thread which don't violate any expectations
+#[test]
+fn ok() {
+    let mut mock = MockFoo::new();
+    mock.expect_foo()
+        .return_const(0u32);
+    let mock = Arc::new(mock);
+    let mock2 = mock.clone();
+    thread::spawn(move || mock2.foo(1)).join().unwrap();
+}
+
+#[test]
+#[should_panic(expected = "MockFoo::foo(5): No matching expectation found")]
+fn reraise_on_checkpoint() {
+    let mock = Arc::new(Mutex::new(MockFoo::new()));
+    let mock2 = mock.clone();
+    thread::spawn(move || mock2.lock().unwrap().foo(5)).join().unwrap_err();
+    // The thread's panic poisoned the mutex
+    let mut guard = mock.lock().unwrap_or_else(|e| e.into_inner());
+    guard.checkpoint();
+    panic!("Shouldn't get here!");
+}
+
+#[test]
+#[should_panic(expected = "MockFoo::foo(5): No matching expectation found")]
+fn reraise_on_drop() {
+    let mock = Arc::new(MockFoo::new());
+    let mock2 = mock.clone();
+    thread::spawn(move || mock2.foo(5)).join().unwrap_err();
+}
+
+#[test]
+#[should_panic(expected = "MockFoo::bar(5): No matching expectation found")]
+fn static_method() {
+    let ctx = MockFoo::bar_context();
+    ctx.expect()
+        .with(predicate::eq(4))
+        .return_const(0u32);
+    thread::spawn(|| MockFoo::bar(5)).join().unwrap_err();
+}
+
+#[test]
+#[should_panic(expected =
+    "MockFoo::foo: Expectation(<anything>) called 2 times which is more than the expected 1")]
+fn times() {
+    let mut mock = MockFoo::new();
+    mock.expect_foo()
+        .times(1)
+        .return_const(0u32);
+    let mock = Arc::new(mock);
+    let mock2 = mock.clone();
+    thread::spawn(move || {
+        mock2.foo(1);
+        mock2.foo(2);
+    }).join().unwrap_err();
+}
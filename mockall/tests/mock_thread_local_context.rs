@@ -0,0 +1,144 @@
+// vim: tw=80
+//! Thread-local Contexts isolate static method expectations between threads
+#![deny(warnings)]
+
+use mockall::*;
+use std::{panic, sync::mpsc, thread};
+
+mock! {
+    Foo {
+        fn foo(x: u32) -> u32;
+        fn bar(x: u32) -> u32;
+        fn baz(x: u32) -> u32;
+        fn bean(x: u32) -> u32;
+        fn boom(x: u32) -> u32;
+        fn moved(x: u32) -> u32;
+        fn pool(x: u32) -> u32;
+    }
+}
+
+#[automock]
+#[allow(unused)]
+mod m {
+    pub fn qux(x: u32) -> u32 { unimplemented!() }
+}
+
+#[test]
+fn attach() {
+    let ctx = MockFoo::bar_context_thread_local();
+    ctx.expect()
+        .return_const(7u32);
+    let handle = ctx.thread_handle();
+    let r = thread::spawn(move || {
+        let _attached = handle.attach();
+        MockFoo::bar(0)
+    }).join().unwrap();
+    assert_eq!(7, r);
+    assert_eq!(1, ctx.calls().len());
+}
+
+/// A thread-local Context dropped on another thread still stops applying to
+/// the thread that created it
+#[test]
+fn drop_elsewhere() {
+    let ctx = MockFoo::moved_context_thread_local();
+    ctx.expect()
+        .return_const(1u32);
+    thread::spawn(move || drop(ctx)).join().unwrap();
+    let ctx = MockFoo::moved_context_thread_local();
+    ctx.expect()
+        .return_const(2u32);
+    assert_eq!(2, MockFoo::moved(0));
+}
+
+/// A thread-local Context doesn't conflict with a global one
+#[test]
+fn global_and_local() {
+    let ctx = MockFoo::baz_context();
+    ctx.expect()
+        .return_const(1u32);
+    let r = thread::spawn(|| {
+        let ctx = MockFoo::baz_context_thread_local();
+        ctx.expect()
+            .return_const(2u32);
+        MockFoo::baz(0)
+    }).join().unwrap();
+    assert_eq!(2, r);
+    assert_eq!(1, MockFoo::baz(0));
+}
+
+#[test]
+fn isolated() {
+    let threads = (0..8u32).map(|i| thread::spawn(move || {
+        let ctx = MockFoo::foo_context_thread_local();
+        ctx.expect()
+            .return_const(i);
+        thread::yield_now();
+        assert_eq!(i, MockFoo::foo(0));
+    })).collect::<Vec<_>>();
+    for t in threads {
+        t.join().unwrap();
+    }
+}
+
+#[test]
+fn module() {
+    let ctx = mock_m::qux_context_thread_local();
+    ctx.expect()
+        .returning(|x| x + 1);
+    assert_eq!(5, mock_m::qux(4));
+}
+
+/// Calls from other threads don't see a thread-local Context's expectations
+#[test]
+fn other_thread() {
+    let ctx = MockFoo::bean_context_thread_local();
+    ctx.expect()
+        .return_const(1u32);
+    let r = thread::spawn(|| {
+        panic::catch_unwind(|| MockFoo::bean(0)).is_err()
+    }).join().unwrap();
+    assert!(r);
+}
+
+/// Once detached, a reused thread, like a pool's worker, no longer sees a
+/// dropped Context's expectations
+#[test]
+fn reused_thread() {
+    let (tx, rx) = mpsc::channel::<Box<dyn FnOnce() + Send>>();
+    let worker = thread::spawn(move || {
+        for job in rx {
+            job();
+        }
+    });
+    let (rtx, rrx) = mpsc::channel();
+    {
+        let ctx = MockFoo::pool_context_thread_local();
+        ctx.expect()
+            .return_const(1u32);
+        let handle = ctx.thread_handle();
+        let rtx = rtx.clone();
+        tx.send(Box::new(move || {
+            let _attached = handle.attach();
+            rtx.send(MockFoo::pool(0)).unwrap();
+        })).unwrap();
+        assert_eq!(1, rrx.recv().unwrap());
+    }
+    tx.send(Box::new(move || {
+        let ctx = MockFoo::pool_context_thread_local();
+        ctx.expect()
+            .return_const(2u32);
+        rtx.send(MockFoo::pool(0)).unwrap();
+    })).unwrap();
+    assert_eq!(2, rrx.recv().unwrap());
+    drop(tx);
+    worker.join().unwrap();
+}
+
+#[test]
+#[should_panic(expected =
+    "MockFoo::boom: A thread-local Context for this method already exists on this thread")]
+fn second_context() {
+    let _ctx0 = MockFoo::boom_context_thread_local();
+    let _ctx1 = MockFoo::boom_context_thread_local();
+}
@@ -0,0 +1,222 @@
+// vim: tw=80
+//! Expectations can be verified without panicking
+#![deny(warnings)]
+
+use mockall::*;
+use std::panic;
+
+trait Foo {
+    fn foo(&self, x: u32) -> u32;
+}
+
+mock! {
+    Bar {
+        fn bar(&self, x: u32) -> u32;
+        fn baz<T: 'static>(&self, t: T) -> u32;
+        fn stat() -> u32;
+    }
+    impl Foo for Bar {
+        fn foo(&self, x: u32) -> u32;
+    }
+}
+
+#[test]
+fn args() {
+    let mut mock = MockBar::new();
+    mock.expect_bar()
+        .times(3)
+        .return_const(0u32);
+    mock.bar(1);
+    mock.bar(2);
+    let errors = mock.verify().unwrap_err();
+    assert_eq!(errors[0].args(), [vec!["1".to_owned()], vec!["2".to_owned()]]);
+    mock.bar(3);
+}
+
+#[test]
+fn context() {
+    let ctx = MockBar::stat_context();
+    ctx.expect()
+        .named("stat")
+        .times(1)
+        .return_const(0u32);
+    let errors = ctx.verify().unwrap_err();
+    assert_eq!(1, errors.len());
+    assert_eq!("MockBar::stat", errors[0].method());
+    assert_eq!("stat", errors[0].description());
+    MockBar::stat();
+    assert_eq!(Ok(()), ctx.verify());
+}
+
+/// verify should not clear expectations
+#[test]
+#[should_panic(expected =
+    "MockBar::bar: Expectation(<anything>) called 0 time(s) which is fewer than expected 1")]
+fn does_not_clear() {
+    let mut mock = MockBar::new();
+    mock.expect_bar()
+        .times(1)
+        .return_const(0u32);
+    assert!(mock.verify().is_err());
+}
+
+#[test]
+fn fewer() {
+    let mut mock = MockBar::new();
+    mock.expect_bar()
+        .with(predicate::eq(4))
+        .times(2..4)
+        .return_const(0u32);
+    mock.bar(4);
+    let errors = mock.verify().unwrap_err();
+    assert_eq!(1, errors.len());
+    assert_eq!("MockBar::bar", errors[0].method());
+    assert_eq!("var == 4", errors[0].description());
+    assert_eq!(2..4, errors[0].expected());
+    assert_eq!(1, errors[0].count());
+    assert!(errors[0].to_string().starts_with(
+        "MockBar::bar: Expectation(var == 4) called 1 time(s) which is fewer than expected 2 (set at"));
+    mock.bar(4);
+}
+
+#[test]
+fn fewer_kind() {
+    let mut mock = MockBar::new();
+    mock.expect_bar()
+        .times(1)
+        .return_const(0u32);
+    let errors = mock.verify().unwrap_err();
+    assert_eq!(ErrorKind::CallCount, errors[0].kind());
+    assert!(errors[0].location().is_some());
+    mock.bar(0);
+}
+
+#[test]
+fn generic_method() {
+    let mut mock = MockBar::new();
+    mock.expect_baz::<i16>()
+        .times(1)
+        .return_const(0u32);
+    assert_eq!("MockBar::baz", mock.verify().unwrap_err()[0].method());
+    mock.baz(5i16);
+    assert_eq!(Ok(()), mock.verify());
+}
+
+/// Calls with generic parameters that have no expectations are reported
+#[test]
+fn generic_method_no_match() {
+    let mut mock = MockBar::new();
+    mock.expect_baz::<i16>()
+        .return_const(0u32);
+    let mock = panic::AssertUnwindSafe(mock);
+    panic::catch_unwind(|| mock.baz(5u8)).unwrap_err();
+    let errors = mock.verify().unwrap_err();
+    assert_eq!(1, errors.len());
+    assert_eq!(ErrorKind::NoMatch, errors[0].kind());
+    assert_eq!(errors[0].args(), [vec!["<u8>".to_owned()]]);
+}
+
+/// Excess calls are reported, if their panics were caught
+#[test]
+fn more() {
+    let mut mock = MockBar::new();
+    mock.expect_bar()
+        .times(1)
+        .return_const(0u32);
+    mock.bar(0);
+    let mock = panic::AssertUnwindSafe(mock);
+    panic::catch_unwind(|| mock.bar(0)).unwrap_err();
+    let errors = mock.verify().unwrap_err();
+    assert_eq!(2, errors[0].count());
+    assert!(errors[0].to_string().starts_with(
+        "MockBar::bar: Expectation(<anything>) called 2 time(s) which is more than the expected 1 (set at"));
+}
+
+/// Calls that matched no expectation are reported, if their panics were
+/// caught
+#[test]
+fn no_match() {
+    let mut mock = MockBar::new();
+    mock.expect_bar()
+        .with(predicate::eq(1))
+        .return_const(0u32);
+    let mock = panic::AssertUnwindSafe(mock);
+    panic::catch_unwind(|| mock.bar(2)).unwrap_err();
+    let errors = mock.verify().unwrap_err();
+    assert_eq!(1, errors.len());
+    assert_eq!(ErrorKind::NoMatch, errors[0].kind());
+    assert_eq!("MockBar::bar", errors[0].method());
+    assert_eq!(None, errors[0].location());
+    assert_eq!(errors[0].args(), [vec!["2".to_owned()]]);
+    assert!(errors[0].to_string().starts_with(
+        "MockBar::bar(2): No matching expectation found"));
+}
+
+#[test]
+fn ok() {
+    let mut mock = MockBar::new();
+    mock.expect_bar()
+        .times(1)
+        .return_const(0u32);
+    mock.bar(0);
+    assert_eq!(Ok(()), mock.verify());
+}
+
+/// Calls made before their prerequisites are reported, if their panics were
+/// caught
+#[test]
+fn prerequisite() {
+    let mut mock = MockBar::new();
+    let bar = mock.expect_bar()
+        .times(1)
+        .return_const(0u32)
+        .prerequisite();
+    mock.expect_foo()
+        .named("foo")
+        .return_const(0u32)
+        .after(&[&bar]);
+    let mock = panic::AssertUnwindSafe(mock);
+    panic::catch_unwind(|| mock.foo(0)).unwrap_err();
+    mock.bar(0);
+    let errors = mock.verify().unwrap_err();
+    assert_eq!(1, errors.len());
+    assert_eq!(ErrorKind::Prerequisite, errors[0].kind());
+    assert_eq!("MockBar::foo", errors[0].method());
+    assert_eq!("foo", errors[0].description());
+    assert!(errors[0].to_string().contains("Prerequisite violation"));
+}
+
+/// Calls made out of sequence are reported, if their panics were caught
+#[test]
+fn sequence() {
+    let mut seq = Sequence::new();
+    let mut mock = MockBar::new();
+    mock.expect_bar()
+        .times(1)
+        .in_sequence(&mut seq)
+        .return_const(0u32);
+    mock.expect_foo()
+        .times(1..)
+        .in_sequence(&mut seq)
+        .return_const(0u32);
+    let mock = panic::AssertUnwindSafe(mock);
+    panic::catch_unwind(|| mock.foo(0)).unwrap_err();
+    mock.bar(0);
+    mock.foo(0);
+    let errors = mock.verify().unwrap_err();
+    assert_eq!(1, errors.len());
+    assert_eq!(ErrorKind::Sequence, errors[0].kind());
+    assert_eq!("MockBar::foo", errors[0].method());
+    assert!(errors[0].to_string().contains("Method sequence violation"));
+}
+
+#[test]
+fn trait_method() {
+    let mut mock = MockBar::new();
+    mock.expect_foo()
+        .times(1)
+        .return_const(0u32);
+    let errors = mock.verify().unwrap_err();
+    assert_eq!("MockBar::foo", errors[0].method());
+    mock.foo(0);
+}
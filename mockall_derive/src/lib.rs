@@ -9,7 +9,7 @@
 #![cfg_attr(test, deny(warnings))]
 
 use cfg_if::cfg_if;
-use proc_macro2::{Span, TokenStream};
+use proc_macro2::{Span, TokenStream, TokenTree};
 use quote::{ToTokens, format_ident, quote};
 use std::{
     collections::{HashMap, HashSet},
@@ -536,6 +536,54 @@ fn find_lifetimes(ty: &Type) -> HashSet<Lifetime> {
     }
 }
 
+/// Is `ty` certainly `'static`?  That is, does it contain no references, no
+/// lifetimes except `'static`, and none of the type parameters in `generics`?
+/// Errs on the side of `false`.
+fn is_static_type(ty: &Type, generics: &Generics) -> bool {
+    match ty {
+        Type::Array(ta) => is_static_type(ta.elem.as_ref(), generics),
+        Type::Group(tg) => is_static_type(tg.elem.as_ref(), generics),
+        Type::Never(_) => true,
+        Type::Paren(tp) => is_static_type(tp.elem.as_ref(), generics),
+        Type::Path(tp) => {
+            tp.qself.is_none() && is_static_path(&tp.path, generics)
+        },
+        Type::Slice(ts) => is_static_type(ts.elem.as_ref(), generics),
+        Type::TraitObject(tto) => {
+            tto.bounds.iter().all(|bound| match bound {
+                TypeParamBound::Lifetime(lt) => lt.ident == "static",
+                TypeParamBound::Trait(tb) => tb.lifetimes.is_none() &&
+                    is_static_path(&tb.path, generics)
+            })
+        },
+        Type::Tuple(tt) => {
+            tt.elems.iter().all(|ty| is_static_type(ty, generics))
+        },
+        _ => false
+    }
+}
+
+fn is_static_path(path: &Path, generics: &Generics) -> bool {
+    let first = &path.segments.first().unwrap().ident;
+    if first == "Self" ||
+        generics.type_params().any(|tp| tp.ident == *first)
+    {
+        return false;
+    }
+    path.segments.iter().all(|seg| match &seg.arguments {
+        PathArguments::None => true,
+        PathArguments::AngleBracketed(abga) => {
+            abga.args.iter().all(|arg| match arg {
+                GenericArgument::Lifetime(lt) => lt.ident == "static",
+                GenericArgument::Type(ty) => is_static_type(ty, generics),
+                GenericArgument::Binding(b) => is_static_type(&b.ty, generics),
+                _ => false
+            })
+        },
+        PathArguments::Parenthesized(_) => false
+    })
+}
+
 
 struct AttrFormatter<'a>{
     attrs: &'a [Attribute],
@@ -592,6 +640,18 @@ impl<'a> AttrFormatter<'a> {
     }
 }
 
+/// Is this a `#[passthrough]` or `#[mockall::passthrough]` attribute?
+fn is_passthrough(attr: &Attribute) -> bool {
+    let segs = attr.path.segments.iter()
+        .map(|seg| seg.ident.to_string())
+        .collect::<Vec<_>>();
+    match &segs[..] {
+        [only] => only == "passthrough",
+        [krate, last] => krate == "mockall" && last == "passthrough",
+        _ => false
+    }
+}
+
 /// Determine if this Pat is any kind of `self` binding
 fn pat_is_self(pat: &Pat) -> bool {
     if let Pat::Ident(pi) = pat {
@@ -601,6 +661,16 @@ fn pat_is_self(pat: &Pat) -> bool {
     }
 }
 
+/// Does this token stream mention the given identifier anywhere, even inside of
+/// a group?
+fn mentions_ident(ts: TokenStream, name: &str) -> bool {
+    ts.into_iter().any(|tt| match tt {
+        TokenTree::Group(g) => mentions_ident(g.stream(), name),
+        TokenTree::Ident(i) => i == name,
+        _ => false
+    })
+}
+
 /// Add `levels` `super::` to the path.  Return the number of levels added.
 fn supersuperfy_path(path: &mut Path, levels: usize) -> usize {
     if let Some(t) = path.segments.last_mut() {
@@ -1049,6 +1119,16 @@ pub fn mock(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     do_mock(input.into()).into()
 }
 
+#[proc_macro_attribute]
+pub fn passthrough(_attrs: proc_macro::TokenStream,
+                   input: proc_macro::TokenStream)
+    -> proc_macro::TokenStream
+{
+    // The attribute only means something to automock and mock!, which remove
+    // it from the methods that they mock.
+    input
+}
+
 #[proc_macro_attribute]
 pub fn automock(attrs: proc_macro::TokenStream, input: proc_macro::TokenStream)
     -> proc_macro::TokenStream
@@ -1365,6 +1445,89 @@ mod gen_keyid {
     }
 }
 
+mod is_passthrough {
+    use super::*;
+
+    fn check(attr: TokenStream) -> bool {
+        let item: ItemFn = parse2(quote!(#attr fn foo() {})).unwrap();
+        is_passthrough(&item.attrs[0])
+    }
+
+    #[test]
+    fn bare() {
+        assert!(check(quote!(#[passthrough])));
+    }
+
+    #[test]
+    fn foreign() {
+        assert!(!check(quote!(#[tracing::passthrough])));
+        assert!(!check(quote!(#[foo::mockall::passthrough])));
+    }
+
+    #[test]
+    fn qualified() {
+        assert!(check(quote!(#[mockall::passthrough])));
+    }
+}
+
+mod is_static_type {
+    use super::*;
+
+    fn check(ty: TokenStream) -> bool {
+        let ty: Type = parse2(ty).unwrap();
+        let generics: Generics = parse2(quote!(<T>)).unwrap();
+        is_static_type(&ty, &generics)
+    }
+
+    #[test]
+    fn concrete() {
+        assert!(check(quote!(Vec<(u32, [String; 2])>)));
+    }
+
+    #[test]
+    fn generic() {
+        assert!(!check(quote!(Option<T>)));
+        assert!(!check(quote!(T::Item)));
+    }
+
+    #[test]
+    fn lifetime() {
+        assert!(!check(quote!(Cow<'a, str>)));
+        assert!(check(quote!(Cow<'static, str>)));
+    }
+
+    #[test]
+    fn reference() {
+        assert!(!check(quote!(Vec<&u32>)));
+    }
+
+    #[test]
+    fn trait_object() {
+        assert!(check(quote!(Box<dyn Debug + Send>)));
+        assert!(!check(quote!(Box<dyn Fn(u32)>)));
+    }
+}
+
+mod mentions_ident {
+    use super::*;
+
+    #[test]
+    fn absent() {
+        assert!(!mentions_ident(quote!(Vec<u32>), "Self"));
+    }
+
+    #[test]
+    fn nested() {
+        assert!(mentions_ident(quote!(Vec<(u32, Option<Self>)>), "Self"));
+    }
+
+    // Only whole identifiers count
+    #[test]
+    fn substring() {
+        assert!(!mentions_ident(quote!(SelfRef), "Self"));
+    }
+}
+
 mod merge_generics {
     use super::*;
 
@@ -26,6 +26,179 @@ fn dedynify(ty: &mut Type) {
     }
 }
 
+/// A callback argument that an expectation can invoke
+struct Callback {
+    /// The callback's argument types
+    inputs: Vec<Type>,
+    output: Type,
+    /// Does it return `()`?
+    unit: bool,
+    /// Is it a `fn` pointer, rather than a `Box<dyn Fn*>`?
+    fn_ptr: bool,
+    /// Is it an `FnOnce`, which must be taken to be invoked?
+    once: bool,
+    /// Can it be stored, to invoke later?
+    deferrable: bool,
+    /// Is it `Send`, so that any thread may invoke it later?
+    send: bool
+}
+
+impl Callback {
+    /// Parse a type like `Box<dyn FnMut(u32) -> u32>` or `fn(u32)`.  Returns
+    /// `None` unless all of the callback's argument types are `'static`.
+    fn new(ty: &Type, generics: &Generics) -> Option<Callback> {
+        let (inputs, output, fn_ptr, once, deferrable, send) = match ty {
+            Type::BareFn(bf) if bf.lifetimes.is_none() &&
+                bf.variadic.is_none() =>
+            {
+                let inputs = bf.inputs.iter()
+                    .map(|arg| arg.ty.clone())
+                    .collect::<Vec<_>>();
+                (inputs, bf.output.clone(), true, false, true, true)
+            },
+            Type::Path(tp) if tp.qself.is_none() => {
+                let seg = tp.path.segments.last().unwrap();
+                if seg.ident != "Box" {
+                    return None;
+                }
+                let tto = match &seg.arguments {
+                    PathArguments::AngleBracketed(abga)
+                        if abga.args.len() == 1 => match &abga.args[0]
+                    {
+                        GenericArgument::Type(Type::TraitObject(tto)) => tto,
+                        _ => return None
+                    },
+                    _ => return None
+                };
+                let mut f = None;
+                let mut once = false;
+                let mut deferrable = true;
+                let mut send = false;
+                for bound in tto.bounds.iter() {
+                    match bound {
+                        TypeParamBound::Lifetime(lt) => {
+                            deferrable &= lt.ident == "static";
+                        },
+                        TypeParamBound::Trait(tb) => {
+                            let seg = tb.path.segments.last().unwrap();
+                            if ["Fn", "FnMut", "FnOnce"].iter()
+                                .any(|s| seg.ident == *s)
+                            {
+                                if tb.lifetimes.is_some() {
+                                    return None;
+                                }
+                                f = Some(&seg.arguments);
+                                once = seg.ident == "FnOnce";
+                            }
+                            send |= seg.ident == "Send";
+                        }
+                    }
+                }
+                match f {
+                    Some(PathArguments::Parenthesized(pga)) => {
+                        let inputs = pga.inputs.iter()
+                            .cloned()
+                            .collect::<Vec<_>>();
+                        (inputs, pga.output.clone(), false, once, deferrable,
+                         send)
+                    },
+                    _ => return None
+                }
+            },
+            _ => return None
+        };
+        if !inputs.iter().all(|ty| is_static_type(ty, generics)) {
+            return None;
+        }
+        let (output, unit) = match output {
+            ReturnType::Default => (parse2(quote!(())).unwrap(), true),
+            ReturnType::Type(_, ty) => (*ty, false)
+        };
+        let deferrable = deferrable && is_static_type(&output, generics);
+        Some(Callback{inputs, output, unit, fn_ptr, once, deferrable, send})
+    }
+}
+
+/// Generate code that invokes callback argument `ident`, number `index`,
+/// with the arguments produced by `__mockall_args`, or defers it to
+/// `__mockall_trigger`.
+fn invoke_callback(ident: &Ident, cb: &Callback, index: usize, funcname: &str)
+    -> TokenStream
+{
+    let inputs = &cb.inputs;
+    let output = &cb.output;
+    let argnames = (0..inputs.len())
+        .map(|i| format_ident!("__mockall_a{}", i))
+        .collect::<Vec<_>>();
+    let call = |f: TokenStream| if cb.unit {
+        quote!(#f(#(#argnames, )*);)
+    } else {
+        quote!(let _ = #f(#(#argnames, )*);)
+    };
+    // Take the callback, leaving a placeholder for the return function that
+    // names the expectation method that took it
+    let take = |taker: &str| if cb.fn_ptr {
+        quote!(let __mockall_f = *#ident;)
+    } else {
+        let msg = format!("{}: argument {} was already taken by {}",
+                          funcname, index, taker);
+        // Only an FnMut needs to be mut
+        quote!(
+            #[allow(unused_mut)]
+            let mut __mockall_f = ::std::mem::replace(#ident,
+                Box::new(|#(_: #inputs),*| -> #output { panic!(#msg) }));
+        )
+    };
+    let now = if cb.once {
+        let take = take("invoke_arg");
+        let call = call(quote!(__mockall_f));
+        quote!(#take #call)
+    } else {
+        call(quote!(#ident))
+    };
+    let unpack = quote!(
+        let (#(#argnames, )*) = *__mockall_a
+            .downcast::<(#(#inputs, )*)>()
+            .unwrap();
+    );
+    let later = if cb.deferrable {
+        let take = take("invoke_arg_later");
+        let call = call(quote!(__mockall_f));
+        // A Send invocation may be fired on any thread
+        let defer = if cb.send {
+            quote!(defer_send)
+        } else {
+            quote!(defer)
+        };
+        quote!(
+            #take
+            __mockall_t.#defer(Box::new(move || { #unpack #call }));
+        )
+    } else {
+        quote!(unreachable!())
+    };
+    quote!(
+        let __mockall_a = __mockall_args();
+        match __mockall_trigger {
+            None => { #unpack #now },
+            Some(__mockall_t) => { #later }
+        }
+    )
+}
+
+/// Is `ty` one of the common unsized types, like `str` or a trait object?
+/// Values of those types can't be written through a `&mut`, nor type-erased.
+fn is_unsized(ty: &Type) -> bool {
+    match ty {
+        Type::Paren(tp) => is_unsized(&tp.elem),
+        Type::Path(tp) => tp.qself.is_none() &&
+            ["CStr", "OsStr", "Path", "str"].iter()
+                .any(|name| tp.path.segments.last().unwrap().ident == name),
+        Type::Slice(_) | Type::TraitObject(_) => true,
+        _ => false
+    }
+}
+
 /// Convert a special reference type like "&str" into a reference to its owned
 /// type like "&String".
 fn destrify(ty: &mut Type) {
@@ -91,6 +264,47 @@ fn destrify(ty: &mut Type) {
     }
 }
 
+/// Does the method return a reference that borrows from its arguments, rather
+/// than from `self`?  That is, is its output `&'a T` for some lifetime `'a`
+/// that is a generic parameter of the method, mentioned by an argument but not
+/// by the receiver?
+///
+/// The Expectation can't own such a return value, so it must get it from a
+/// closure that is generic over the arguments' lifetimes.
+fn borrows_from_args(sig: &Signature) -> bool {
+    let tr = match &sig.output {
+        ReturnType::Type(_, ty) => match ty.as_ref() {
+            Type::Reference(tr) => tr,
+            _ => return false
+        },
+        ReturnType::Default => return false
+    };
+    let lt = match &tr.lifetime {
+        Some(lt) if lt.ident != "static" => lt,
+        _ => return false
+    };
+    let mut alts = HashSet::<Lifetime>::default();
+    for arg in sig.inputs.iter() {
+        match arg {
+            FnArg::Receiver(r) => {
+                if let Some((_, Some(rlt))) = &r.reference {
+                    if rlt == lt {
+                        return false;
+                    }
+                }
+            },
+            FnArg::Typed(pt) => {
+                alts.extend(find_lifetimes(pt.ty.as_ref()));
+            }
+        }
+    }
+    let borrowable = |l: &Lifetime| alts.contains(l) &&
+        sig.generics.lifetimes().any(|ltd| ltd.lifetime == *l);
+    borrowable(lt) && find_lifetimes(tr.elem.as_ref())
+        .iter()
+        .all(|l| l.ident == "static" || borrowable(l))
+}
+
 /// Return the owned version of the input.
 fn ownify(ty: &Type) -> Type {
     if let Type::Reference(ref tr) = &ty {
@@ -147,6 +361,7 @@ pub(crate) struct Builder<'a> {
     call_levels: Option<usize>,
     levels: usize,
     parent: Option<&'a Ident>,
+    passthrough: Option<&'a Block>,
     sig: &'a Signature,
     struct_: Option<&'a Ident>,
     struct_generics: Option<&'a Generics>,
@@ -207,11 +422,15 @@ impl<'a> Builder<'a> {
                 is_static = false;
             }
         }
+        let return_borrowed = borrows_from_args(self.sig);
         let output = match self.sig.output {
             ReturnType::Default => Type::Tuple(TypeTuple {
                     paren_token: token::Paren::default(),
                     elems: Punctuated::new()
                 }),
+            ReturnType::Type(_, ref ty) if return_borrowed => {
+                supersuperfy(ty, self.levels)
+            },
             ReturnType::Type(_, ref ty) => {
                 let mut output_ty = supersuperfy(&**ty, self.levels);
                 destrify(&mut output_ty);
@@ -220,11 +439,18 @@ impl<'a> Builder<'a> {
             }
         };
         supersuperfy_generics(&mut declosured_generics, self.levels);
-        let owned_output = ownify(&output);
+        let owned_output = if return_borrowed {
+            output.clone()
+        } else {
+            ownify(&output)
+        };
         let mut return_ref = false;
         let mut return_refmut = false;
+        // A borrowed output gets an ordinary Expectation, whose closures are
+        // generic over the arguments' lifetimes.
         if let Type::Reference(ref tr) = &output {
-            if tr.lifetime.as_ref().map_or(true, |lt| lt.ident != "static")
+            if !return_borrowed &&
+                tr.lifetime.as_ref().map_or(true, |lt| lt.ident != "static")
             {
                 if tr.mutability.is_none() {
                     return_ref = true;
@@ -237,6 +463,12 @@ impl<'a> Builder<'a> {
             compile_error(self.sig.span(),
                 "Mockall cannot mock static methods that return non-'static references.  It's unclear what the return value's lifetime should be.");
         }
+        if self.passthrough.is_some() &&
+            self.sig.generics.type_params().next().is_some()
+        {
+            compile_error(self.sig.span(),
+                "Mockall does not yet support passthrough for generic methods");
+        }
         let struct_generics = self.struct_generics.cloned()
             .unwrap_or_default();
         let (type_generics, salifetimes, srlifetimes) = split_lifetimes(
@@ -246,11 +478,17 @@ impl<'a> Builder<'a> {
                               Box::new(owned_output.clone()))
         );
         let srltg = lifetimes_to_generics(&srlifetimes);
+        // A borrowed output's lifetimes all belong to the arguments
+        let mrt = if return_borrowed {
+            ReturnType::Default
+        } else {
+            ReturnType::Type(<Token![->]>::default(),
+                             Box::new(owned_output.clone()))
+        };
         let (call_generics, malifetimes, mrlifetimes) = split_lifetimes(
             declosured_generics,
             &declosured_inputs,
-            &ReturnType::Type(<Token![->]>::default(),
-                              Box::new(owned_output.clone()))
+            &mrt
         );
         let mrltg = lifetimes_to_generics(&mrlifetimes);
         let cgenerics = merge_generics(&type_generics, &call_generics);
@@ -284,9 +522,11 @@ impl<'a> Builder<'a> {
             mod_ident: self.parent.unwrap_or(&Ident::new("FIXME", Span::call_site())).clone(),
             output,
             owned_output,
+            passthrough: self.passthrough.cloned(),
             predexprs,
             predty,
             refpredty,
+            return_borrowed,
             return_ref,
             return_refmut,
             sig: self.sig.clone(),
@@ -322,6 +562,7 @@ impl<'a> Builder<'a> {
             levels: 0,
             call_levels: None,
             parent: None,
+            passthrough: None,
             sig,
             struct_: None,
             struct_generics: None,
@@ -336,6 +577,13 @@ impl<'a> Builder<'a> {
         self
     }
 
+    /// Supply the original method's body, to run when no expectation
+    /// matches a call
+    pub fn passthrough(&mut self, block: &'a Block) -> &mut Self {
+        self.passthrough = Some(block);
+        self
+    }
+
     /// Supply the name of the parent struct, if any
     pub fn struct_(&mut self, ident: &'a Ident) -> &mut Self {
         self.struct_= Some(ident);
@@ -389,11 +637,17 @@ pub(crate) struct MockFunction {
     /// If the real output type is a non-'static reference, then it will differ
     /// from this field.
     owned_output: Type,
+    /// The original method's body, which handles calls that no expectation
+    /// matches
+    passthrough: Option<Block>,
     /// Expressions that create the predicate arguments from the call arguments
     predexprs: Vec<TokenStream>,
     /// Types used for Predicates.  Will be almost the same as args, but every
     /// type will be a non-reference type.
     predty: Vec<Type>,
+    /// Does the function return a reference that borrows from its arguments?
+    /// If so, then `return_ref` and `return_refmut` are both false.
+    return_borrowed: bool,
     /// Does the function return a non-'static reference? 
     return_ref: bool,
     /// Does the function return a mutable reference? 
@@ -452,37 +706,73 @@ impl MockFunction {
         } else {
             Ident::new("call", Span::call_site())
         };
+        let predexprs = &self.predexprs;
         if self.is_static {
             let outer_mod_path = self.outer_mod_path(modname);
+            let body = quote!({
+                let no_match_msg = #no_match_msg;
+                {
+                    // A panic here will poison the lock.  But every other user
+                    // of the lock recovers from that.
+                    let __mockall_guard = ::mockall::lock(&__mockall_storage);
+                    __mockall_guard.#call#tbf(#(#call_exprs,)*)
+                }.unwrap_or_else(|__mockall_why|
+                    ::mockall::fail(std::format!("{}{}", no_match_msg,
+                                                 __mockall_why))
+                )
+            });
+            let body = quote!({
+                let __mockall_violations = ::mockall::lock(&__mockall_storage)
+                    .violations();
+                __mockall_violations.watch(move || #body)
+            });
+            let body = if let Some(block) = &self.passthrough {
+                quote!({
+                    // Release the lock before running the original body, which
+                    // may call this method again.
+                    let __mockall_handled = ::mockall::lock(&__mockall_storage)
+                        .handles(#(#predexprs,)*);
+                    if __mockall_handled #body else #block
+                })
+            } else {
+                body
+            };
+            let body = quote!({
+                let __mockall_storage = #outer_mod_path::expectations();
+                #body
+            });
             quote!(
                 // Don't add a doc string.  The original is included in #attrs
                 #(#attrs)*
-                #vis #sig {
-                    let no_match_msg = #no_match_msg;
-                    {
-                        let __mockall_guard = #outer_mod_path::EXPECTATIONS
-                            .lock().unwrap();
-                        /*
-                         * TODO: catch panics, then gracefully release the mutex
-                         * so it won't be poisoned.  This requires bounding any
-                         * generic parameters with UnwindSafe
-                         */
-                        /* std::panic::catch_unwind(|| */
-                        __mockall_guard.#call#tbf(#(#call_exprs,)*)
-                        /*)*/
-                    }.expect(&no_match_msg)
-                }
+                #vis #sig #body
             )
         } else {
+            let body = quote!({
+                let no_match_msg = #no_match_msg;
+                self.#substruct_obj #name.#call#tbf(#(#call_exprs,)*)
+                .unwrap_or_else(|__mockall_why|
+                    ::mockall::fail(std::format!("{}{}", no_match_msg,
+                                                 __mockall_why))
+                )
+            });
+            let body = quote!({
+                self.#substruct_obj #name.violations()
+                    .watch(move || #body)
+            });
+            let body = if let Some(block) = &self.passthrough {
+                quote!({
+                    if self.#substruct_obj #name.handles(#(#predexprs,)*)
+                        #body
+                    else
+                        #block
+                })
+            } else {
+                body
+            };
             quote!(
                 // Don't add a doc string.  The original is included in #attrs
                 #(#attrs)*
-                #vis #sig {
-                    let no_match_msg = #no_match_msg;
-                    self.#substruct_obj #name.#call#tbf(#(#call_exprs,)*)
-                    .expect(&no_match_msg)
-                }
-
+                #vis #sig #body
             )
         }
     }
@@ -497,8 +787,14 @@ impl MockFunction {
             quote!(
                 #(#attrs)*
                 {
-                    let __mockall_timeses = #inner_mod_ident::EXPECTATIONS.lock()
-                        .unwrap()
+                    // Release the lock before re-raising any violations, so
+                    // it won't be poisoned.
+                    let __mockall_violations =
+                        ::mockall::lock(&#inner_mod_ident::expectations())
+                        .violations();
+                    __mockall_violations.reraise();
+                    let __mockall_timeses =
+                        ::mockall::lock(&#inner_mod_ident::expectations())
                         .checkpoint()
                         .collect::<Vec<_>>();
                 }
@@ -509,6 +805,16 @@ impl MockFunction {
         }
     }
 
+    /// Return this method's contribution to its parent's verify method, which
+    /// extends `errors` with any unsatisfied expectations.
+    pub fn verify(&self, errors: &Ident) -> impl ToTokens {
+        let attrs = AttrFormatter::new(&self.attrs)
+            .doc(false)
+            .format();
+        let name = &self.name();
+        quote!(#(#attrs)* #errors.extend(self.#name.verify());)
+    }
+
     /// Return a function that creates a Context object for this function
     ///
     /// # Arguments
@@ -524,20 +830,143 @@ impl MockFunction {
             modname.map(|m| format!("{}/", m)).unwrap_or_default(),
             self.inner_mod_ident(),
             self.name());
+        let locked_docstr = format!("Like [`{}_context`](#method.{}_context), but first wait for any other `Context` for the `{}` method to drop.  Useful for tests that may run concurrently.",
+            self.name(), self.name(), self.name());
         let context_ident = format_ident!("{}_context", self.name());
+        let locked_ident = format_ident!("{}_context_locked", self.name());
+        let local_docstr = format!("Create a thread-local [`Context`]({}{}/struct.Context.html) for mocking the `{}` method.  Its expectations apply only to calls from the current thread, so tests that use it may run concurrently.",
+            modname.map(|m| format!("{}/", m)).unwrap_or_default(),
+            self.inner_mod_ident(),
+            self.name());
+        let local_ident = format_ident!("{}_context_thread_local",
+                                        self.name());
         let (_, tg, _) = self.type_generics.split_for_impl();
         let outer_mod_path = self.outer_mod_path(modname);
+        // The lock shared by the locked Contexts of all of the mock's static
+        // methods
+        let mock_lock = if let Some(s) = &self.struct_ {
+            let struct_modname = gen_mod_ident(s, None);
+            quote!(#struct_modname::__MOCKALL_CONTEXT_LOCK)
+        } else {
+            quote!(__MOCKALL_CONTEXT_LOCK)
+        };
         let v = &self.call_vis;
         quote!(
             #(#attrs)*
             #[doc = #context_docstr]
             #v fn #context_ident() -> #outer_mod_path::Context #tg
             {
-                #outer_mod_path::Context::default()
+                #outer_mod_path::Context::new(None)
+            }
+            #(#attrs)*
+            #[doc = #locked_docstr]
+            #v fn #locked_ident() -> #outer_mod_path::Context #tg
+            {
+                #outer_mod_path::Context::new(Some(&*#mock_lock))
+            }
+            #(#attrs)*
+            #[doc = #local_docstr]
+            #v fn #local_ident() -> #outer_mod_path::Context #tg
+            {
+                #outer_mod_path::Context::new_thread_local()
+            }
+        )
+    }
+
+    /// Generate code for the calls_ method, which returns the call history
+    pub fn calls(&self) -> impl ToTokens {
+        let attrs = AttrFormatter::new(&self.attrs)
+            .doc(false)
+            .format();
+        let name = self.name();
+        let calls_ident = format_ident!("calls_{}", &name);
+        let funcname = &self.sig.ident;
+        let (_, tg, _) = if self.is_method_generic() {
+            &self.egenerics
+        } else {
+            &self.call_generics
+        }.split_for_impl();
+        let (ig, _, wc) = self.call_generics.split_for_impl();
+        let tbf = tg.as_turbofish();
+        let vis = &self.call_vis;
+        let substruct_obj = if let Some(trait_) = &self.trait_ {
+            let ident = format_ident!("{}_expectations", trait_);
+            quote!(#ident.)
+        } else {
+            quote!()
+        };
+        let docstr = format!("Return every call made to the `{}` method since the last checkpoint, oldest first.",
+            funcname);
+        quote!(
+            #[doc = #docstr]
+            #(#attrs)*
+            #vis fn #calls_ident #ig(&self)
+                -> ::std::vec::Vec<::mockall::Call>
+                #wc
+            {
+                self.#substruct_obj #name.calls#tbf()
             }
         )
     }
 
+    /// Generate code for the var_ method, which returns the variables created
+    /// by `return_var_keyed`.  Only methods that return by mutable reference
+    /// have one.
+    pub fn var(&self) -> Option<impl ToTokens> {
+        if !self.return_refmut {
+            return None;
+        }
+        let attrs = AttrFormatter::new(&self.attrs)
+            .doc(false)
+            .format();
+        let name = self.name();
+        let var_ident = format_ident!("var_{}", &name);
+        let funcname = &self.sig.ident;
+        // Unlike self.owned_output, this must be valid outside of the
+        // private module
+        let owned_output = match &self.sig.output {
+            ReturnType::Type(_, ty) => {
+                let mut output = (**ty).clone();
+                destrify(&mut output);
+                dedynify(&mut output);
+                ownify(&output)
+            },
+            ReturnType::Default => unreachable!()
+        };
+        let mut tgenerics = if self.is_method_generic() {
+            self.egenerics.clone()
+        } else {
+            self.call_generics.clone()
+        };
+        if !tgenerics.params.is_empty() {
+            tgenerics.params.push(parse_quote!(MockallK));
+        }
+        let (_, tg, _) = tgenerics.split_for_impl();
+        let tbf = tg.as_turbofish();
+        let mut vgenerics = self.call_generics.clone();
+        vgenerics.params.push(parse_quote!(MockallK: 'static));
+        let (ig, _, wc) = vgenerics.split_for_impl();
+        let vis = &self.call_vis;
+        let substruct_obj = if let Some(trait_) = &self.trait_ {
+            let ident = format_ident!("{}_expectations", trait_);
+            quote!(#ident.)
+        } else {
+            quote!()
+        };
+        let docstr = format!("Return the variable that the `{}` method's `return_var_keyed` expectations created for `key`, if any.  Panics if `key` is not the type of their keys.",
+            funcname);
+        Some(quote!(
+            #[doc = #docstr]
+            #(#attrs)*
+            #vis fn #var_ident #ig(&self, key: &MockallK)
+                -> Option<&#owned_output>
+                #wc
+            {
+                self.#substruct_obj #name.var#tbf(key)
+            }
+        ))
+    }
+
     /// Generate a code fragment that will print a description of the invocation
     fn desc(&self) -> impl ToTokens {
         let argnames = &self.argnames;
@@ -546,9 +975,83 @@ impl MockFunction {
         } else {
             format!("{}::{}", self.mod_ident, self.sig.ident)
         };
-        let fields = vec!["{:?}"; argnames.len()].join(", ");
+        let fields = vec!["{}"; argnames.len()].join(", ");
         let fstr = format!("{}({})", name, fields);
-        quote!(std::format!(#fstr, #(::mockall::MaybeDebugger(&#argnames)),*))
+        quote!({
+            use ::mockall::{MaybeDebugFallback, MaybeDebugViaDebug};
+            std::format!(#fstr,
+                #((&&::mockall::MaybeDebugger(&#argnames)).debug_string()),*)
+        })
+    }
+
+    /// For each argument that is a `&mut` of a `'static`, sized type, the type
+    /// that an expectation can write through it, and whether that is the element
+    /// type of a slice.  Other arguments can't be written.
+    fn out_args(&self) -> Vec<Option<(Type, bool)>> {
+        self.argty.iter()
+            .map(|ty| match ty {
+                Type::Reference(tr) if tr.mutability.is_some() => {
+                    match tr.elem.as_ref() {
+                        Type::Slice(ts) => Some(((*ts.elem).clone(), true)),
+                        elem => Some((elem.clone(), false))
+                    }
+                },
+                _ => None
+            }.filter(|(ty, _)| is_static_type(ty, &self.egenerics) &&
+                     !is_unsized(ty)))
+            .collect()
+    }
+
+    /// For each argument that is a callback that an expectation can invoke,
+    /// its name and description
+    fn callbacks(&self) -> Vec<Option<(&Ident, Callback)>> {
+        self.argnames.iter()
+            .zip(self.argty.iter())
+            .map(|(argname, ty)| match argname {
+                Pat::Ident(pi) if pi.subpat.is_none() &&
+                    pi.by_ref.is_none() =>
+                {
+                    Callback::new(ty, &self.egenerics)
+                        .map(|cb| (&pi.ident, cb))
+                },
+                _ => None
+            }).collect()
+    }
+
+    /// The names of the arguments that an expectation can write through
+    fn out_names(&self) -> Vec<&Pat> {
+        self.out_args().iter()
+            .zip(self.argnames.iter())
+            .filter(|(arg, _)| arg.is_some())
+            .map(|(_, argname)| argname)
+            .collect()
+    }
+
+    /// For each argument, an expression for the `Option<CaptureFn>` that can
+    /// capture it, or `None` if its type might not be `'static`.  Such
+    /// arguments can't be captured at all.
+    fn capturers(&self) -> Vec<Option<TokenStream>> {
+        self.predty.iter()
+            .map(|ty| if is_static_type(ty, &self.egenerics) {
+                Some(quote!({
+                    use ::mockall::{CaptureFallback, CaptureViaToOwned};
+                    (&&::mockall::Capturer::<#ty>::new()).capturer()
+                }))
+            } else {
+                None
+            }).collect()
+    }
+
+    /// Generate a code fragment that renders each argument for the call
+    /// history
+    fn record_args(&self) -> impl ToTokens {
+        let argnames = &self.argnames;
+        quote!({
+            use ::mockall::{MaybeDebugFallback, MaybeDebugViaDebug};
+            ::std::vec![
+                #((&&::mockall::MaybeDebugger(&#argnames)).debug_string()),*
+            ]
+        })
     }
 
     /// Generate code for the expect_ method
@@ -563,14 +1066,37 @@ impl MockFunction {
     // wouldn't need to know that.
     pub fn expect(&self, modname: &Ident, self_args: Option<&PathArguments>)
         -> impl ToTokens
+    {
+        let docstr = format!("Create an [`Expectation`]({}/{}/struct.Expectation.html) for mocking the `{}` method",
+            modname, self.inner_mod_ident(), &self.sig.ident);
+        self.expectation_fn(modname, self_args, "expect", &docstr)
+    }
+
+    /// Return a function that returns the fallback Expectation for this
+    /// method, which handles calls that no other expectation matches.  The
+    /// arguments are the same as for `expect`.
+    pub fn fallback(&self, modname: &Ident, self_args: Option<&PathArguments>)
+        -> impl ToTokens
+    {
+        let docstr = format!("Return the fallback [`Expectation`]({}/{}/struct.Expectation.html) for the `{}` method, which handles calls that no other expectation matches",
+            modname, self.inner_mod_ident(), &self.sig.ident);
+        self.expectation_fn(modname, self_args, "fallback", &docstr)
+    }
+
+    /// Common code for `expect` and `fallback`.  Generates a function named
+    /// like `<method>_<name>` that forwards to the method's Expectations
+    /// object.
+    fn expectation_fn(&self, modname: &Ident,
+                      self_args: Option<&PathArguments>,
+                      method: &str, docstr: &str) -> TokenStream
     {
         let attrs = AttrFormatter::new(&self.attrs)
             .doc(false)
             .format();
         let name = self.name();
-        let expect_ident = format_ident!("expect_{}", &name);
+        let expect_ident = format_ident!("{}_{}", method, &name);
+        let method = format_ident!("{}", method);
         let expectation_obj = self.expectation_obj(self_args);
-        let funcname = &self.sig.ident;
         let (_, tg, _) = if self.is_method_generic() {
             &self.egenerics
         } else {
@@ -586,30 +1112,21 @@ impl MockFunction {
         let tbf = tg.as_turbofish();
         let vis = &self.call_vis;
 
-        #[cfg(not(feature = "nightly_derive"))]
-        let must_use = quote!(#[must_use =
-                "Must set return value when not using the \"nightly\" feature"
-            ]);
-        #[cfg(feature = "nightly_derive")]
-        let must_use = quote!();
-
         let substruct_obj = if let Some(trait_) = &self.trait_ {
             let ident = format_ident!("{}_expectations", trait_);
             quote!(#ident.)
         } else {
             quote!()
         };
-        let docstr = format!("Create an [`Expectation`]({}/{}/struct.Expectation.html) for mocking the `{}` method",
-            modname, self.inner_mod_ident(), funcname);
         quote!(
-            #must_use
             #[doc = #docstr]
             #(#attrs)*
+            #[track_caller]
             #vis fn #expect_ident #ig(&mut self)
                -> &mut #modname::#expectation_obj
                #wc
             {
-                self.#substruct_obj #name.expect#tbf()
+                self.#substruct_obj #name.#method#tbf()
             }
         )
     }
@@ -780,6 +1297,7 @@ impl MockFunction {
                     boxed::Box,
                     mem,
                     ops::{DerefMut, Range},
+                    string::String,
                     sync::Mutex,
                     vec::Vec,
                 };
@@ -794,6 +1312,65 @@ impl MockFunction {
             }
         )
     }
+
+    /// Generate code that makes `receiver`'s copy of this method forward
+    /// unmatched calls to the real object in `real`, an `Arc<Mutex<_>>`.
+    /// `qself` is the real object's type, qualified by the method's trait.
+    ///
+    /// Returns `None` for methods whose calls can't be forwarded, like static
+    /// methods, generic methods, methods that return references or other
+    /// types with lifetimes (Mockall may have replaced `'_` with `'static`),
+    /// and methods whose mocked signature differs from the real one.
+    pub fn spy_forward(&self, receiver: &TokenStream, real: &Ident,
+                       qself: &TokenStream) -> Option<TokenStream>
+    {
+        let deref = match self.sig.inputs.first() {
+            Some(FnArg::Receiver(Receiver{reference: Some(_), mutability: None,
+                ..})) => quote!(&*),
+            Some(FnArg::Receiver(Receiver{reference: Some(_),
+                mutability: Some(_), ..})) => quote!(&mut *),
+            _ => return None
+        };
+        if self.sig.asyncness.is_some() ||
+            self.sig.unsafety.is_some() ||
+            self.sig.generics.type_params().next().is_some() ||
+            self.sig.generics.where_clause.is_some() ||
+            self.return_ref || self.return_refmut ||
+            !find_lifetimes(&self.output).is_empty()
+        {
+            return None;
+        }
+        let inputs = &self.argty;
+        let output = &self.output;
+        let sigtypes = quote!(#(#inputs)* #output);
+        let mut forbidden = vec![
+            String::from("Self"), String::from("dyn"), String::from("impl")
+        ];
+        if let Some(s) = &self.struct_ {
+            forbidden.push(s.to_string());
+        }
+        if forbidden.iter().any(|f| mentions_ident(sigtypes.clone(), f)) {
+            // The mock's signature uses the mock struct, or a trait object
+            // that may have replaced an `impl Trait`.
+            return None;
+        }
+        let attrs = AttrFormatter::new(&self.attrs)
+            .doc(false)
+            .format();
+        let argnames = &self.argnames;
+        let name = self.name();
+        Some(quote!(
+            #(#attrs)*
+            {
+                let #real = #real.clone();
+                #receiver.#name.fallback()
+                    .returning(move |#(#argnames),*|
+                        <#qself>::#name(#deref ::mockall::lock(&#real),
+                            #(#argnames),*)
+                    );
+            }
+        ))
+    }
 }
 
 /// Holds parts of the expectation that are common for all output types
@@ -808,6 +1385,7 @@ impl<'a> ToTokens for Common<'a> {
         let hrtb = self.f.hrtb();
         let funcname = self.f.funcname();
         let (ig, tg, wc) = self.f.cgenerics.split_for_impl();
+        let (e_ig, e_tg, e_wc) = self.f.egenerics.split_for_impl();
         let lg = lifetimes_to_generics(&self.f.alifetimes);
         let refpredty = &self.f.refpredty;
         let with_generics_idents = (0..self.f.predty.len())
@@ -825,11 +1403,96 @@ impl<'a> ToTokens for Common<'a> {
         let boxed_withargs = argnames.iter()
             .map(|aa| quote!(Box::new(#aa), ))
             .collect::<TokenStream>();
+        let capturers = self.f.capturers();
+        let idx = (0..capturers.len()).collect::<Vec<_>>();
+        let callbacks = self.f.callbacks();
+        let callback_types = callbacks.iter()
+            .map(|cb| match cb {
+                Some((_, cb)) => {
+                    let inputs = &cb.inputs;
+                    let deferrable = cb.deferrable;
+                    quote!(Some(::mockall::CallbackType::new::<(#(#inputs,)*)>(
+                        #deferrable)))
+                },
+                None => quote!(None)
+            }).collect::<Vec<_>>();
+        let (mut cb_idx, mut cb_params, mut cb_invokes) =
+            (Vec::new(), Vec::new(), Vec::new());
+        for (i, cb) in callbacks.iter().enumerate() {
+            if let Some((ident, cb)) = cb {
+                cb_idx.push(i);
+                let ty = &self.f.argty[i];
+                cb_params.push(quote!(#ident: &mut #ty));
+                cb_invokes.push(invoke_callback(ident, cb, i, &funcname));
+            }
+        }
+        let out_args = self.f.out_args();
+        let out_types = out_args.iter()
+            .map(|arg| match arg {
+                Some((ty, false)) =>
+                    quote!(Some(::mockall::OutArgType::value::<#ty>())),
+                Some((ty, true)) =>
+                    quote!(Some(::mockall::OutArgType::slice::<#ty>())),
+                None => quote!(None)
+            }).collect::<Vec<_>>();
+        let (mut out_idx, mut out_params, mut out_exprs) =
+            (Vec::new(), Vec::new(), Vec::new());
+        for (i, (arg, argname)) in out_args.iter().zip(argnames).enumerate() {
+            match arg {
+                Some((ty, false)) => {
+                    out_params.push(quote!(#argname: &mut #ty));
+                    out_exprs.push(quote!(::mockall::OutArg::Value(#argname)));
+                },
+                Some((ty, true)) => {
+                    out_params.push(quote!(#argname: &mut [#ty]));
+                    out_exprs.push(quote!(::mockall::OutArg::Slice(
+                        #argname.iter_mut()
+                        .map(|__mockall_e| __mockall_e as &mut dyn ::std::any::Any)
+                        .collect())));
+                },
+                None => continue
+            }
+            out_idx.push(i);
+        }
+        let capture_types = capturers.iter()
+            .map(|c| match c {
+                Some(c) => quote!(#c.map(|__mockall_c|
+                    (__mockall_c.type_id, __mockall_c.type_name))),
+                None => quote!(None::<(::std::any::TypeId, &'static str)>)
+            }).collect::<Vec<_>>();
+        let capture_exprs = capturers.iter()
+            .zip(argnames.iter())
+            .map(|(c, argname)| match c {
+                Some(c) =>
+                    quote!(__mockall_store((#c.unwrap().convert)(#argname))),
+                None => quote!(unreachable!())
+            }).collect::<Vec<_>>();
         quote!(
             /// Holds the stuff that is independent of the output type
             struct Common #ig #wc {
+                after: Vec<::mockall::Prerequisite>,
+                /// Sequence and prerequisite violations, for `verify`
+                errors: ::mockall::ErrorLog,
+                /// Where this expectation was created
+                location: &'static ::std::panic::Location<'static>,
                 matcher: Mutex<Matcher #tg>,
-                seq_handle: Option<::mockall::SeqHandle>,
+                /// A human-readable name, used instead of the matcher to
+                /// identify this expectation
+                name: Option<String>,
+                prerequisite: Option<::mockall::Prerequisite>,
+                /// Callback argument indices, with their arguments and any
+                /// Trigger that defers them
+                invocations: Vec<(usize, ::mockall::ArgsFn,
+                                  Option<::mockall::Trigger>)>,
+                /// Argument indices and the values to write through them
+                arg_writes: Vec<(usize, ::mockall::ArgWrite)>,
+                /// Argument indices and the captors that store them
+                captures: Vec<(usize,
+                    Box<dyn Fn(Box<dyn ::std::any::Any + Send>) + Send + Sync>
+                )>,
+                /// Shared with any scripted return values
+                script: ::std::sync::Arc<::mockall::ScriptState>,
+                seq_handles: Vec<::mockall::SeqHandle>,
                 times: ::mockall::Times
             }
 
@@ -837,8 +1500,17 @@ impl<'a> ToTokens for Common<'a> {
             {
                 fn default() -> Self {
                     Common {
+                        after: Vec::new(),
+                        errors: ::mockall::ErrorLog::default(),
+                        location: ::std::panic::Location::caller(),
                         matcher: Mutex::new(Matcher::default()),
-                        seq_handle: None,
+                        name: None,
+                        prerequisite: None,
+                        arg_writes: Vec::new(),
+                        captures: Vec::new(),
+                        invocations: Vec::new(),
+                        script: ::std::sync::Arc::default(),
+                        seq_handles: Vec::new(),
                         times: ::mockall::Times::default()
                     }
                 }
@@ -847,29 +1519,193 @@ impl<'a> ToTokens for Common<'a> {
             impl #ig Common #tg #wc {
                 fn call(&self, desc: &str) {
                     self.times.call()
-                        .unwrap_or_else(|m| {
-                            let desc = std::format!(
-                                "{}", self.matcher.lock().unwrap());
-                            panic!("{}: Expectation({}) {}", #funcname, desc,
-                                m);
-                        });
+                        .unwrap_or_else(|m| self.fail(&m));
                     self.verify_sequence(desc);
-                    if self.times.is_satisfied() {
-                        self.satisfy_sequence()
+                    self.verify_prerequisites(desc);
+                    self.report_satisfaction();
+                }
+
+                fn after(&mut self,
+                    __mockall_prereqs: &[&::mockall::Prerequisite])
+                {
+                    self.after.extend(__mockall_prereqs.iter()
+                        .map(|__mockall_p| (*__mockall_p).clone()));
+                }
+
+                // Unreachable if the method has no arguments
+                #[allow(unreachable_code)]
+                fn capture<MockallT>(&mut self, __mockall_index: usize,
+                    __mockall_captor: &::mockall::Captor<MockallT>)
+                    where MockallT: Send + 'static
+                {
+                    let __mockall_owned: Option<(::std::any::TypeId, &str)> =
+                        match __mockall_index
+                    {
+                        #(#idx => #capture_types,)*
+                        _ => panic!("{}: has no argument {}", #funcname,
+                                    __mockall_index)
+                    };
+                    match __mockall_owned {
+                        None => panic!("{}: argument {} can't be captured.  Only arguments of 'static types that impl ToOwned can be",
+                                       #funcname, __mockall_index),
+                        Some((__mockall_id, __mockall_name)) if __mockall_id !=
+                            ::std::any::TypeId::of::<MockallT>() =>
+                        {
+                            panic!("{}: argument {} is captured as {}, not {}",
+                                   #funcname, __mockall_index, __mockall_name,
+                                   ::std::any::type_name::<MockallT>())
+                        },
+                        Some(_) => ()
+                    }
+                    self.captures.push(
+                        (__mockall_index, __mockall_captor.storer()));
+                }
+
+                /// The callback argument `__mockall_index`, or `None` if
+                /// there is no such argument.
+                fn callback(&self, __mockall_index: usize)
+                    -> Option<Option<::mockall::CallbackType>>
+                {
+                    match __mockall_index {
+                        #(#idx => Some(#callback_types),)*
+                        _ => None
+                    }
+                }
+
+                fn copy_into_arg<MockallT>(&mut self, __mockall_index: usize,
+                    __mockall_values: &[MockallT])
+                    where MockallT: Clone + Send + 'static
+                {
+                    let __mockall_w = ::mockall::copy_into(#funcname,
+                        __mockall_index, self.out_arg(__mockall_index),
+                        __mockall_values.to_vec());
+                    self.arg_writes.push((__mockall_index, __mockall_w));
+                }
+
+                fn invoke_arg<MockallA>(&mut self, __mockall_index: usize,
+                    __mockall_args: MockallA,
+                    __mockall_trigger: Option<&::mockall::Trigger>)
+                    where MockallA: Clone + Send + 'static
+                {
+                    let __mockall_f = ::mockall::invoke_args(#funcname,
+                        __mockall_index, self.callback(__mockall_index),
+                        __mockall_args, __mockall_trigger.is_some());
+                    self.invocations.push((__mockall_index, __mockall_f,
+                                           __mockall_trigger.cloned()));
+                }
+
+                /// Invoke the callback arguments of a call, or defer them.
+                fn invoke_callbacks #lg (&self, #(#cb_params, )*) {
+                    for (__mockall_index, __mockall_args, __mockall_trigger) in
+                        self.invocations.iter()
+                    {
+                        match __mockall_index {
+                            #(#cb_idx => {#cb_invokes},)*
+                            _ => unreachable!()
+                        }
                     }
                 }
 
+                /// The type that can be written through argument
+                /// `__mockall_index`, or `None` if there is no such argument.
+                fn out_arg(&self, __mockall_index: usize)
+                    -> Option<Option<::mockall::OutArgType>>
+                {
+                    match __mockall_index {
+                        #(#idx => Some(#out_types),)*
+                        _ => None
+                    }
+                }
+
+                fn set_arg_mut<MockallT>(&mut self, __mockall_index: usize,
+                    __mockall_value: MockallT)
+                    where MockallT: Clone + Send + 'static
+                {
+                    let __mockall_w = ::mockall::set_arg(#funcname,
+                        __mockall_index, self.out_arg(__mockall_index),
+                        __mockall_value);
+                    self.arg_writes.push((__mockall_index, __mockall_w));
+                }
+
+                /// Write through the `&mut` arguments of a call.
+                fn write_args #lg (&self, #(#out_params, )*) {
+                    for (__mockall_index, __mockall_w) in self.arg_writes.iter()
+                    {
+                        match __mockall_index {
+                            #(#out_idx => {
+                                if let Err(__mockall_m) = __mockall_w(#out_exprs)
+                                {
+                                    self.fail(&__mockall_m);
+                                }
+                            },)*
+                            _ => unreachable!()
+                        }
+                    }
+                }
+
+                /// Store the arguments of a call in this expectation's
+                /// captors.
+                #[allow(clippy::ptr_arg)]
+                #[allow(unused_variables)]
+                fn store_captures #lg (&self, #( #argnames: &#predty, )*) {
+                    for (__mockall_index, __mockall_store) in self.captures.iter()
+                    {
+                        match __mockall_index {
+                            #(#idx => #capture_exprs,)*
+                            _ => unreachable!()
+                        }
+                    }
+                }
+
+                /// Explain why this expectation did not handle a call
+                #[allow(clippy::ptr_arg)]
+                fn explain #lg (&self, #( #argnames: &#predty, )*) -> String {
+                    let __mockall_desc = self.desc();
+                    let __mockall_matcher = self.matcher.lock().unwrap();
+                    if __mockall_matcher.matches(#(#argnames, )*) &&
+                        self.script.is_exhausted()
+                    {
+                        std::format!("Expectation({}) (set at {}) matched, but it ran out of scripted return values",
+                            __mockall_desc, self.location)
+                    } else if __mockall_matcher.matches(#(#argnames, )*) {
+                        std::format!("Expectation({}) (set at {}) matched, but it was already called {} time(s), the most allowed",
+                            __mockall_desc, self.location,
+                            self.times.count())
+                    } else {
+                        std::format!("Expectation({}) (set at {}) did not match: {}",
+                            __mockall_desc, self.location,
+                            __mockall_matcher.explain(#(#argnames, )*))
+                    }
+                }
+
+                /// Describe this expectation by its name, or by its matcher
+                /// if it has none.
+                fn desc(&self) -> String {
+                    match &self.name {
+                        Some(__mockall_name) => __mockall_name.clone(),
+                        None => std::format!("{}", self.matcher.lock().unwrap())
+                    }
+                }
+
+                /// Panic with a message identifying this expectation and the
+                /// place where it was created.
+                fn fail(&self, __mockall_msg: &str) -> ! {
+                    ::mockall::fail(std::format!(
+                        "{}: Expectation({}) {} (set at {})", #funcname,
+                        self.desc(), __mockall_msg, self.location));
+                }
+
                 fn in_sequence(&mut self, __mockall_seq: &mut ::mockall::Sequence)
                     -> &mut Self
                 {
-                    assert!(self.times.is_exact(),
-                        "Only Expectations with an exact call count have sequences");
-                    self.seq_handle = Some(__mockall_seq.next_handle());
+                    self.seq_handles.push(__mockall_seq.next_handle());
+                    self.describe_handles();
+                    self.report_satisfaction();
                     self
                 }
 
                 fn is_done(&self) -> bool {
-                    self.times.is_done()
+                    self.times.is_done() || self.script.is_exhausted()
                 }
 
                 #[allow(clippy::ptr_arg)]
@@ -877,14 +1713,60 @@ impl<'a> ToTokens for Common<'a> {
                     self.matcher.lock().unwrap().matches(#(#argnames, )*)
                 }
 
+                fn named(&mut self, __mockall_name: &str) {
+                    self.name = Some(String::from(__mockall_name));
+                    self.describe_handles();
+                }
+
                 /// Forbid this expectation from ever being called.
                 fn never(&mut self) {
                     self.times.never();
+                    self.report_satisfaction();
                 }
 
-                fn satisfy_sequence(&self) {
-                    if let Some(__mockall_handle) = &self.seq_handle {
-                        __mockall_handle.satisfy()
+                /// Forget any script, because a return setter that isn't
+                /// scripted replaced it.
+                fn unscript(&mut self) {
+                    self.script = ::std::sync::Arc::default();
+                }
+
+                fn prerequisite(&mut self) -> ::mockall::Prerequisite {
+                    let __mockall_p = self.prerequisite
+                        .get_or_insert_with(::mockall::Prerequisite::new)
+                        .clone();
+                    self.describe_handles();
+                    self.report_satisfaction();
+                    __mockall_p
+                }
+
+                /// Tell the sequences and the prerequisite, if any, how to
+                /// identify this expectation in error messages.
+                fn describe_handles(&self) {
+                    if self.seq_handles.is_empty() && self.prerequisite.is_none()
+                    {
+                        return;
+                    }
+                    let __mockall_desc = std::format!(
+                        "{}: Expectation({}) (set at {})",
+                        #funcname, self.desc(), self.location);
+                    for __mockall_handle in self.seq_handles.iter() {
+                        __mockall_handle.describe(__mockall_desc.clone())
+                    }
+                    if let Some(__mockall_p) = &self.prerequisite {
+                        __mockall_p.describe(__mockall_desc)
+                    }
+                }
+
+                /// Tell the sequences and the prerequisite, if any, whether
+                /// this expectation has been called its minimum number of
+                /// times.
+                fn report_satisfaction(&self) {
+                    let __mockall_satisfied = self.times.is_satisfied();
+                    for __mockall_handle in self.seq_handles.iter() {
+                        __mockall_handle.satisfy(__mockall_satisfied)
+                    }
+                    if let Some(__mockall_p) = &self.prerequisite {
+                        __mockall_p.satisfy(__mockall_satisfied)
                     }
                 }
 
@@ -893,7 +1775,8 @@ impl<'a> ToTokens for Common<'a> {
                 fn times<MockallR>(&mut self, __mockall_r: MockallR)
                     where MockallR: Into<::mockall::TimesRange>
                 {
-                    self.times.times(__mockall_r)
+                    self.times.times(__mockall_r);
+                    self.report_satisfaction();
                 }
 
                 fn with<#with_generics>(&mut self, #with_args)
@@ -901,6 +1784,8 @@ impl<'a> ToTokens for Common<'a> {
                     let mut __mockall_guard = self.matcher.lock().unwrap();
                     *__mockall_guard.deref_mut() =
                         Matcher::Pred(Box::new((#boxed_withargs)));
+                    drop(__mockall_guard);
+                    self.describe_handles();
                 }
 
                 fn withf<MockallF>(&mut self, __mockall_f: MockallF)
@@ -910,6 +1795,8 @@ impl<'a> ToTokens for Common<'a> {
                     let mut __mockall_guard = self.matcher.lock().unwrap();
                     *__mockall_guard.deref_mut() =
                          Matcher::Func(Box::new(__mockall_f));
+                    drop(__mockall_guard);
+                    self.describe_handles();
                 }
 
                 fn withf_st<MockallF>(&mut self, __mockall_f: MockallF)
@@ -921,11 +1808,51 @@ impl<'a> ToTokens for Common<'a> {
                          Matcher::FuncSt(
                              ::mockall::Fragile::new(Box::new(__mockall_f))
                         );
+                    drop(__mockall_guard);
+                    self.describe_handles();
+                }
+
+                /// Check this expectation's call count without panicking, and
+                /// return it with any violations that its calls raised.
+                /// `args` holds the arguments of each call that it handled.
+                fn verify(&self, __mockall_args: Vec<Vec<String>>)
+                    -> Vec<::mockall::Error>
+                {
+                    let mut __mockall_errors = self.errors.errors();
+                    if !self.times.is_valid() {
+                        __mockall_errors.push(::mockall::Error::new(#funcname,
+                            self.desc(), self.location, &self.times,
+                            __mockall_args));
+                    }
+                    __mockall_errors
+                }
+
+                /// Record a violation for `verify`, and panic with it.
+                fn violation(&self, __mockall_kind: ::mockall::ErrorKind,
+                    __mockall_msg: String) -> !
+                {
+                    self.errors.push(::mockall::Error::violation(
+                        __mockall_kind, #funcname, self.desc(), self.location,
+                        &self.times, __mockall_msg.clone()));
+                    ::mockall::fail(__mockall_msg)
+                }
+
+                fn verify_prerequisites(&self, desc: &str) {
+                    for __mockall_p in self.after.iter() {
+                        if let Err(__mockall_m) = __mockall_p.verify(desc) {
+                            self.violation(::mockall::ErrorKind::Prerequisite,
+                                __mockall_m);
+                        }
+                    }
                 }
 
                 fn verify_sequence(&self, desc: &str) {
-                    if let Some(__mockall_handle) = &self.seq_handle {
-                        __mockall_handle.verify(desc)
+                    for __mockall_handle in self.seq_handles.iter() {
+                        if let Err(__mockall_m) = __mockall_handle.verify(desc)
+                        {
+                            self.violation(::mockall::ErrorKind::Sequence,
+                                __mockall_m);
+                        }
                     }
                 }
             }
@@ -934,16 +1861,22 @@ impl<'a> ToTokens for Common<'a> {
                 fn drop(&mut self) {
                     if !::std::thread::panicking() && !self.times.is_satisfied()
                     {
-                        let desc = std::format!(
-                            "{}", self.matcher.lock().unwrap());
-                        panic!("{}: Expectation({}) called {} time(s) which is fewer than expected {}",
-                               #funcname,
-                               desc,
-                               self.times.count(),
-                               self.times.minimum());
+                        self.fail(&std::format!(
+                            "called {} time(s) which is fewer than expected {}",
+                            self.times.count(),
+                            self.times.minimum()));
                     }
                 }
             }
+
+            impl #e_ig std::fmt::Debug for Expectation #e_tg #e_wc {
+                fn fmt(&self, f: &mut std::fmt::Formatter)
+                    -> std::fmt::Result
+                {
+                    write!(f, "{}: Expectation({}) (set at {})", #funcname,
+                        self.desc(), self.common.location)
+                }
+            }
         ).to_tokens(tokens);
     }
 }
@@ -973,8 +1906,49 @@ impl<'a> ToTokens for CommonExpectationMethods<'a> {
             .collect::<TokenStream>();
         let v = &self.f.privmod_vis;
         quote!(
+            /// Store an owned copy of argument number `index`, counting from
+            /// zero, in `captor` on every call that this expectation handles.
+            ///
+            /// Panics if that argument's type is generic, contains lifetimes,
+            /// or doesn't convert to `T` with `ToOwned`.  See
+            /// [`Captor`](../../../mockall/struct.Captor.html).
+            #v fn capture<MockallT>(&mut self, __mockall_index: usize,
+                __mockall_captor: &::mockall::Captor<MockallT>) -> &mut Self
+                where MockallT: Send + 'static
+            {
+                self.common.capture(__mockall_index, __mockall_captor);
+                self
+            }
+
+            /// On every call that this expectation handles, copy `values` into
+            /// the start of argument number `index`, counting from zero.
+            ///
+            /// The argument must be a `&mut [T]`, where `T` is `'static`.
+            /// This happens before the expectation computes its return value,
+            /// so it works with any of the `return*` methods.
+            #v fn copy_into_arg<MockallT>(&mut self, __mockall_index: usize,
+                __mockall_values: &[MockallT]) -> &mut Self
+                where MockallT: Clone + Send + 'static
+            {
+                self.common.copy_into_arg(__mockall_index, __mockall_values);
+                self
+            }
+
+            /// Forbid this expectation from being called until each of the
+            /// given [`Prerequisite`](../../../mockall/struct.Prerequisite.html)s
+            /// has been satisfied.
+            #v fn after(&mut self,
+                __mockall_prereqs: &[&::mockall::Prerequisite])
+                -> &mut Self
+            {
+                self.common.after(__mockall_prereqs);
+                self
+            }
+
             /// Add this expectation to a
             /// [`Sequence`](../../../mockall/struct.Sequence.html).
+            ///
+            /// An expectation may belong to several sequences at once.
             #v fn in_sequence(&mut self, __mockall_seq: &mut ::mockall::Sequence)
                 -> &mut Self
             {
@@ -982,6 +1956,53 @@ impl<'a> ToTokens for CommonExpectationMethods<'a> {
                 self
             }
 
+            /// On every call that this expectation handles, invoke callback
+            /// argument number `index`, counting from zero, with a clone of
+            /// `args`.
+            ///
+            /// The argument must be a `fn` or a `Box<dyn Fn*>`, including a
+            /// generic `Fn*` argument, and `args` must be a tuple of its
+            /// argument types, which must be `'static`.  This happens before
+            /// the expectation computes its return value.  An `FnOnce`
+            /// argument is consumed, and the return value can't use it.
+            #v fn invoke_arg<MockallA>(&mut self, __mockall_index: usize,
+                __mockall_args: MockallA) -> &mut Self
+                where MockallA: Clone + Send + 'static
+            {
+                self.common.invoke_arg(__mockall_index, __mockall_args, None);
+                self
+            }
+
+            /// Like [`invoke_arg`](#method.invoke_arg), but instead of
+            /// invoking the callback, store the invocation in `trigger` to
+            /// run when it [fires](../../../mockall/struct.Trigger.html#method.fire).
+            ///
+            /// The callback is consumed, even if it is an `Fn` or `FnMut`
+            /// that [`invoke_arg`](#method.invoke_arg) would merely borrow,
+            /// so the return value can't use it.
+            #v fn invoke_arg_later<MockallA>(&mut self,
+                __mockall_index: usize,
+                __mockall_args: MockallA,
+                __mockall_trigger: &::mockall::Trigger) -> &mut Self
+                where MockallA: Clone + Send + 'static
+            {
+                self.common.invoke_arg(__mockall_index, __mockall_args,
+                    Some(__mockall_trigger));
+                self
+            }
+
+            /// Describe this expectation by its name, or by its matcher if it
+            /// has none.
+            fn desc(&self) -> String {
+                self.common.desc()
+            }
+
+            /// Explain why this expectation did not handle a call
+            #[allow(clippy::ptr_arg)]
+            fn explain #lg (&self, #(#argnames: &#predty, )*) -> String {
+                self.common.explain(#(#argnames, )*)
+            }
+
             fn is_done(&self) -> bool {
                 self.common.is_done()
             }
@@ -992,6 +2013,15 @@ impl<'a> ToTokens for CommonExpectationMethods<'a> {
                 self.common.matches(#(#argnames, )*)
             }
 
+            /// Give this expectation a human-readable name.  It will be used
+            /// instead of the matcher to identify the expectation in panic
+            /// messages, in sequence violations, in `Debug` output, and in the
+            /// call history.
+            #v fn named(&mut self, __mockall_name: &str) -> &mut Self {
+                self.common.named(__mockall_name);
+                self
+            }
+
             /// Forbid this expectation from ever being called.
             #v fn never(&mut self) -> &mut Self {
                 self.common.never();
@@ -1009,6 +2039,29 @@ impl<'a> ToTokens for CommonExpectationMethods<'a> {
                 self.times(1)
             }
 
+            /// Return a
+            /// [`Prerequisite`](../../../mockall/struct.Prerequisite.html)
+            /// that other expectations can be called
+            /// [`after`](#method.after).  It will be satisfied once this
+            /// expectation has been called its minimum number of times.
+            #v fn prerequisite(&mut self) -> ::mockall::Prerequisite {
+                self.common.prerequisite()
+            }
+
+            /// On every call that this expectation handles, assign a clone of
+            /// `value` through argument number `index`, counting from zero.
+            ///
+            /// The argument must be a `&mut T`, where `T` is `'static`.  This
+            /// happens before the expectation computes its return value, so it
+            /// works with any of the `return*` methods.
+            #v fn set_arg_mut<MockallT>(&mut self, __mockall_index: usize,
+                __mockall_value: MockallT) -> &mut Self
+                where MockallT: Clone + Send + 'static
+            {
+                self.common.set_arg_mut(__mockall_index, __mockall_value);
+                self
+            }
+
             /// Restrict the number of times that that this method may be called.
             ///
             /// The argument may be:
@@ -1071,38 +2124,177 @@ struct CommonExpectationsMethods<'a> {
 
 impl<'a> ToTokens for CommonExpectationsMethods<'a> {
     fn to_tokens(&self, tokens: &mut TokenStream) {
+        let argnames = &self.f.argnames;
         let (ig, tg, wc) = self.f.egenerics.split_for_impl();
+        let lg = lifetimes_to_generics(&self.f.alifetimes);
+        let predty = &self.f.predty;
+        let funcname = self.f.funcname();
         let v = &self.f.privmod_vis;
+        let handles = if self.f.passthrough.is_some() {
+            let record_args = self.f.record_args();
+            quote!(
+                /// Would an expectation or the fallback handle a call with
+                /// these arguments?  If not, record the call as unmatched, so
+                /// that the caller may handle it some other way.
+                #[allow(clippy::ptr_arg)]
+                #v fn handles #lg (&self, #(#argnames: &#predty, )*) -> bool {
+                    let __mockall_handled = self.expectations.iter()
+                        .any(|__mockall_e|
+                              __mockall_e.matches(#(#argnames, )*) &&
+                              (!__mockall_e.is_done() || self.expectations.len() == 1)) ||
+                        self.fallback.as_ref().map_or(false, |__mockall_f|
+                            __mockall_f.matches(#(#argnames, )*));
+                    if !__mockall_handled {
+                        self.history.record(#record_args, None, None);
+                    }
+                    __mockall_handled
+                }
+            )
+        } else {
+            TokenStream::new()
+        };
         quote!(
             /// A collection of [`Expectation`](struct.Expectations.html)
             /// objects.  Users will rarely if ever use this struct directly.
             #[doc(hidden)]
-            #v struct Expectations #ig ( Vec<Expectation #tg>) #wc;
+            #v struct Expectations #ig #wc {
+                expectations: Vec<Expectation #tg>,
+                /// Calls that matched no expectation, for `verify`
+                errors: ::mockall::ErrorLog,
+                history: ::mockall::CallHistory,
+                /// Handles calls that no other expectation matches
+                fallback: Option<Expectation #tg>,
+                /// Should the fallback be restored after a checkpoint?
+                nice: bool,
+                violations: ::mockall::Violations
+            }
 
             impl #ig Expectations #tg #wc {
+                /// Return every recorded call to this method, oldest first.
+                #v fn calls(&self) -> Vec<::mockall::Call> {
+                    self.history.calls()
+                }
+
                 /// Verify that all current expectations are satisfied and clear
-                /// them.
+                /// them, along with the call history.
                 #v fn checkpoint(&mut self) -> std::vec::Drain<Expectation #tg>
                 {
-                    self.0.drain(..)
+                    self.violations.reraise();
+                    self.errors.clear();
+                    self.history.clear();
+                    self.fallback = if self.nice {
+                        Some(Expectation::default())
+                    } else {
+                        None
+                    };
+                    self.expectations.drain(..)
+                }
+
+                /// Explain why none of the current expectations could
+                /// handle a call.
+                #[allow(clippy::ptr_arg)]
+                fn explain #lg (&self, #(#argnames: &#predty, )*) -> String {
+                    if self.expectations.is_empty() {
+                        return String::from("\n    No expectations have been set");
+                    }
+                    self.expectations.iter()
+                        .enumerate()
+                        .map(|(__mockall_i, __mockall_e)| std::format!(
+                            "\n    {}. {}", __mockall_i,
+                            __mockall_e.explain(#(#argnames, )*)))
+                        .collect()
                 }
 
                 /// Create a new expectation for this method.
+                #[track_caller]
                 #v fn expect(&mut self) -> &mut Expectation #tg
                 {
-                    self.0.push(Expectation::default());
-                    let __mockall_l = self.0.len();
-                    &mut self.0[__mockall_l - 1]
+                    let mut __mockall_e = Expectation::default();
+                    __mockall_e.common.location =
+                        ::std::panic::Location::caller();
+                    self.expectations.push(__mockall_e);
+                    let __mockall_l = self.expectations.len();
+                    &mut self.expectations[__mockall_l - 1]
+                }
+
+                #handles
+
+                /// Record a call that matched no expectation, for `verify`,
+                /// and return the explanation `why`.
+                fn no_match(&self, __mockall_call: String,
+                    __mockall_args: Vec<String>, __mockall_why: String)
+                    -> String
+                {
+                    self.errors.push(::mockall::Error::no_match(#funcname,
+                        &__mockall_call, __mockall_args, &__mockall_why));
+                    __mockall_why
+                }
+
+                /// Return the expectation that handles calls which no other
+                /// expectation matches, creating it if necessary.
+                #[track_caller]
+                #v fn fallback(&mut self) -> &mut Expectation #tg
+                {
+                    let mut __mockall_e = Expectation::default();
+                    __mockall_e.common.location =
+                        ::std::panic::Location::caller();
+                    self.fallback.get_or_insert(__mockall_e)
                 }
 
                 #v fn new() -> Self {
                     Self::default()
                 }
+
+                /// Handle unmatched calls with a default fallback, now and
+                /// after every checkpoint.
+                #v fn nice(&mut self) {
+                    self.nice = true;
+                    if self.fallback.is_none() {
+                        self.fallback = Some(Expectation::default());
+                    }
+                }
+
+                /// Return the record of violations raised by this method's
+                /// calls.
+                #v fn violations(&self) -> ::mockall::Violations {
+                    self.violations.clone()
+                }
+
+                /// Check that every current expectation has been called an
+                /// allowed number of times, and gather the violations that
+                /// calls have raised, without clearing anything.
+                #v fn verify(&self) -> Vec<::mockall::Error> {
+                    let __mockall_calls = self.history.calls();
+                    self.expectations.iter()
+                        .enumerate()
+                        .flat_map(|(__mockall_i, __mockall_e)| {
+                            let __mockall_args = __mockall_calls.iter()
+                                .filter(|__mockall_c|
+                                    __mockall_c.expectation() ==
+                                        Some(__mockall_i))
+                                .map(|__mockall_c| __mockall_c.args().to_vec())
+                                .collect();
+                            __mockall_e.common.verify(__mockall_args)
+                        }).chain(self.errors.errors())
+                        .collect()
+                }
+            }
+            impl #ig Drop for Expectations #tg #wc {
+                fn drop(&mut self) {
+                    self.violations.reraise();
+                }
             }
             impl #ig Default for Expectations #tg #wc
             {
                 fn default() -> Self {
-                    Expectations(Vec::new())
+                    Expectations {
+                        expectations: Vec::new(),
+                        errors: ::mockall::ErrorLog::default(),
+                        history: ::mockall::CallHistory::default(),
+                        fallback: None,
+                        nice: false,
+                        violations: ::mockall::Violations::default()
+                    }
                 }
             }
         ).to_tokens(tokens);
@@ -1150,47 +2342,77 @@ impl<'a> ToTokens for ExpectationGuardCommonMethods<'a> {
             .map(|(argname, id)| quote!(#argname: #id, ))
             .collect::<TokenStream>();
         let v = &self.f.privmod_vis;
+        let consts = if self.f.return_borrowed {
+            None
+        } else {
+            Some(quote!(
+                /// Just like
+                /// [`Expectation::return_const`](struct.Expectation.html#method.return_const)
+                #v fn return_const<MockallOutput>
+                (&mut self, __mockall_c: MockallOutput)
+                    -> &mut Expectation #tg
+                    where MockallOutput: Clone + Into<#output> + Send + 'static
+                {
+                    #expectations.expectations[self.i].return_const(__mockall_c)
+                }
+
+                /// Just like
+                /// [`Expectation::return_const_st`](struct.Expectation.html#method.return_const_st)
+                #v fn return_const_st<MockallOutput>
+                (&mut self, __mockall_c: MockallOutput)
+                    -> &mut Expectation #tg
+                    where MockallOutput: Clone + Into<#output> + 'static
+                {
+                    #expectations.expectations[self.i].return_const_st(__mockall_c)
+                }
+            ))
+        };
         quote!(
+            /// Just like
+            /// [`Expectation::after`](struct.Expectation.html#method.after)
+            #v fn after(&mut self,
+                __mockall_prereqs: &[&::mockall::Prerequisite])
+                -> &mut Expectation #tg
+            {
+                #expectations.expectations[self.i].after(__mockall_prereqs)
+            }
+
             /// Just like
             /// [`Expectation::in_sequence`](struct.Expectation.html#method.in_sequence)
             #v fn in_sequence(&mut self,
                 __mockall_seq: &mut ::mockall::Sequence)
                 -> &mut Expectation #tg
             {
-                #expectations.0[self.i].in_sequence(__mockall_seq)
+                #expectations.expectations[self.i].in_sequence(__mockall_seq)
+            }
+
+            /// Just like
+            /// [`Expectation::named`](struct.Expectation.html#method.named)
+            #v fn named(&mut self, __mockall_name: &str)
+                -> &mut Expectation #tg
+            {
+                #expectations.expectations[self.i].named(__mockall_name)
             }
 
             /// Just like
             /// [`Expectation::never`](struct.Expectation.html#method.never)
             #v fn never(&mut self) -> &mut Expectation #tg {
-                #expectations.0[self.i].never()
+                #expectations.expectations[self.i].never()
             }
 
             /// Just like
             /// [`Expectation::once`](struct.Expectation.html#method.once)
             #v fn once(&mut self) -> &mut Expectation #tg {
-                #expectations.0[self.i].once()
+                #expectations.expectations[self.i].once()
             }
 
             /// Just like
-            /// [`Expectation::return_const`](struct.Expectation.html#method.return_const)
-            #v fn return_const<MockallOutput>
-            (&mut self, __mockall_c: MockallOutput)
-                -> &mut Expectation #tg
-                where MockallOutput: Clone + Into<#output> + Send + 'static
-            {
-                #expectations.0[self.i].return_const(__mockall_c)
+            /// [`Expectation::prerequisite`](struct.Expectation.html#method.prerequisite)
+            #v fn prerequisite(&mut self) -> ::mockall::Prerequisite {
+                #expectations.expectations[self.i].prerequisite()
             }
 
-            /// Just like
-            /// [`Expectation::return_const_st`](struct.Expectation.html#method.return_const_st)
-            #v fn return_const_st<MockallOutput>
-            (&mut self, __mockall_c: MockallOutput)
-                -> &mut Expectation #tg
-                where MockallOutput: Clone + Into<#output> + 'static
-            {
-                #expectations.0[self.i].return_const_st(__mockall_c)
-            }
+            #consts
 
             /// Just like
             /// [`Expectation::returning`](struct.Expectation.html#method.returning)
@@ -1199,7 +2421,7 @@ impl<'a> ToTokens for ExpectationGuardCommonMethods<'a> {
                 where MockallF: #hrtb FnMut(#(#argty, )*)
                     -> #output + Send + 'static
             {
-                #expectations.0[self.i].returning(__mockall_f)
+                #expectations.expectations[self.i].returning(__mockall_f)
             }
 
             /// Just like
@@ -1209,7 +2431,7 @@ impl<'a> ToTokens for ExpectationGuardCommonMethods<'a> {
                 where MockallF: #hrtb FnOnce(#(#argty, )*)
                                 -> #output + Send + 'static
             {
-                #expectations.0[self.i].return_once(__mockall_f)
+                #expectations.expectations[self.i].return_once(__mockall_f)
             }
 
             /// Just like
@@ -1219,7 +2441,7 @@ impl<'a> ToTokens for ExpectationGuardCommonMethods<'a> {
                 where MockallF: #hrtb FnOnce(#(#argty, )*)
                                 -> #output + 'static
             {
-                #expectations.0[self.i].return_once_st(__mockall_f)
+                #expectations.expectations[self.i].return_once_st(__mockall_f)
             }
 
 
@@ -1230,7 +2452,7 @@ impl<'a> ToTokens for ExpectationGuardCommonMethods<'a> {
                 where MockallF: #hrtb FnMut(#(#argty, )*)
                                 -> #output + 'static
             {
-                #expectations.0[self.i].returning_st(__mockall_f)
+                #expectations.expectations[self.i].returning_st(__mockall_f)
             }
 
             /// Just like
@@ -1239,7 +2461,7 @@ impl<'a> ToTokens for ExpectationGuardCommonMethods<'a> {
                 -> &mut Expectation #tg
                 where MockallR: Into<::mockall::TimesRange>
             {
-                #expectations.0[self.i].times(__mockall_r)
+                #expectations.expectations[self.i].times(__mockall_r)
             }
 
             /// Just like
@@ -1247,7 +2469,7 @@ impl<'a> ToTokens for ExpectationGuardCommonMethods<'a> {
             #v fn with<#with_generics> (&mut self, #with_args)
                 -> &mut Expectation #tg
             {
-                #expectations.0[self.i].with(#(#argnames, )*)
+                #expectations.expectations[self.i].with(#(#argnames, )*)
             }
 
             /// Just like
@@ -1257,7 +2479,7 @@ impl<'a> ToTokens for ExpectationGuardCommonMethods<'a> {
                 where MockallF: #hrtb Fn(#(&#predty, )*)
                                 -> bool + Send + 'static
             {
-                #expectations.0[self.i].withf(__mockall_f)
+                #expectations.expectations[self.i].withf(__mockall_f)
             }
 
             /// Just like
@@ -1267,7 +2489,7 @@ impl<'a> ToTokens for ExpectationGuardCommonMethods<'a> {
                 where MockallF: #hrtb Fn(#(&#predty, )*)
                                 -> bool + 'static
             {
-                #expectations.0[self.i].withf_st(__mockall_f)
+                #expectations.expectations[self.i].withf_st(__mockall_f)
             }
         ).to_tokens(tokens);
     }
@@ -1296,13 +2518,9 @@ impl<'a> ToTokens for ConcreteExpectationGuard<'a> {
         let (e_ig, e_tg, e_wc) = e_generics.split_for_impl();
         let (ei_ig, _, _) = e_generics.split_for_impl();
         let v = &self.f.privmod_vis;
+        let storage = static_storage(v, quote!(Expectations #tg));
         quote!(
-            ::mockall::lazy_static! {
-                #[doc(hidden)]
-                #v static ref EXPECTATIONS:
-                    ::std::sync::Mutex<Expectations #tg> =
-                    ::std::sync::Mutex::new(Expectations::new());
-            }
+            #storage
             /// Like an [`&Expectation`](struct.Expectation.html) but
             /// protected by a Mutex guard.  Useful for mocking static
             /// methods.  Forwards accesses to an `Expectation` object.
@@ -1326,11 +2544,12 @@ impl<'a> ToTokens for ConcreteExpectationGuard<'a> {
                 // Should only be called from the mockall_derive generated
                 // code
                 #[doc(hidden)]
+                #[track_caller]
                 #v fn new(mut __mockall_guard: MutexGuard<'__mockall_lt, Expectations #tg>)
                     -> Self
                 {
                     __mockall_guard.expect(); // Drop the &Expectation
-                    let __mockall_i = __mockall_guard.0.len() - 1;
+                    let __mockall_i = __mockall_guard.expectations.len() - 1;
                     ExpectationGuard{guard: __mockall_guard, i: __mockall_i}
                 }
 
@@ -1365,12 +2584,9 @@ impl<'a> ToTokens for GenericExpectationGuard<'a> {
         let fn_params = &self.f.fn_params;
         let tbf = tg.as_turbofish();
         let v = &self.f.privmod_vis;
+        let storage = static_storage(v, quote!(GenericExpectations));
         quote!(
-            ::mockall::lazy_static! {
-                #v static ref EXPECTATIONS:
-                    ::std::sync::Mutex<GenericExpectations> =
-                    ::std::sync::Mutex::new(GenericExpectations::new());
-            }
+            #storage
             /// Like an [`&Expectation`](struct.Expectation.html) but
             /// protected by a Mutex guard.  Useful for mocking static
             /// methods.  Forwards accesses to an `Expectation` object.
@@ -1386,6 +2602,7 @@ impl<'a> ToTokens for GenericExpectationGuard<'a> {
                 // Should only be called from the mockall_derive generated
                 // code
                 #[doc(hidden)]
+                #[track_caller]
                 #v fn new(mut __mockall_guard: MutexGuard<'__mockall_lt, GenericExpectations>)
                     -> Self
                 {
@@ -1397,7 +2614,7 @@ impl<'a> ToTokens for GenericExpectationGuard<'a> {
                         .downcast_mut()
                         .unwrap();
                     __mockall_ee.expect();    // Drop the &Expectation
-                    let __mockall_i = __mockall_ee.0.len() - 1;
+                    let __mockall_i = __mockall_ee.expectations.len() - 1;
                     ExpectationGuard{guard: __mockall_guard, i: __mockall_i,
                         _phantom: ::std::marker::PhantomData}
                 }
@@ -1408,6 +2625,36 @@ impl<'a> ToTokens for GenericExpectationGuard<'a> {
     }
 }
 
+/// Generate the storage for a static method's expectations, of type `ty`.
+/// They're global, unless the current thread has a thread-local `Context`.
+fn static_storage(v: &Visibility, ty: TokenStream) -> TokenStream {
+    quote!(
+        #[doc(hidden)]
+        #v type Storage = ::std::sync::Arc<::std::sync::Mutex<#ty>>;
+        ::mockall::lazy_static! {
+            #[doc(hidden)]
+            #v static ref EXPECTATIONS: Storage = Storage::default();
+        }
+        /// Holds the expectations of one thread's thread-local `Context`,
+        /// if any.  It's shared, so that a `Context` or `ThreadAttachment`
+        /// dropped on another thread still updates the right thread's slot.
+        #[doc(hidden)]
+        #v type LocalSlot =
+            ::std::sync::Arc<::std::sync::Mutex<Option<Storage>>>;
+        ::std::thread_local! {
+            /// The current thread's slot
+            static LOCAL_EXPECTATIONS: LocalSlot = LocalSlot::default();
+        }
+        /// Return the expectations that apply to the current thread.
+        #[doc(hidden)]
+        #v fn expectations() -> Storage {
+            LOCAL_EXPECTATIONS.with(|__mockall_l|
+                ::mockall::lock(__mockall_l).clone()
+            ).unwrap_or_else(|| EXPECTATIONS.clone())
+        }
+    )
+}
+
 /// Generates Context, which manages the context for expectations of static
 /// methods.
 struct Context<'a> {
@@ -1435,27 +2682,49 @@ impl<'a> ToTokens for Context<'a> {
         );
         meth_generics.params.push(GenericParam::Lifetime(ltdef));
         let (meth_ig, _meth_tg, meth_wc) = meth_generics.split_for_impl();
+        let (call_ig, _, call_wc) = self.f.call_generics.split_for_impl();
+        let calls_tbf = if self.f.is_expectation_generic() {
+            let fn_params = &self.f.fn_params;
+            quote!(::<#(#fn_params),*>)
+        } else {
+            quote!()
+        };
         let ctx_fn_params = self.f.struct_generics.type_params()
             .map(|tp| tp.ident.clone())
             .collect::<Punctuated::<Ident, Token![,]>>();
+        let funcname = self.f.funcname();
+        let locked_fn = format!("{}_context_locked", funcname);
         let v = &self.f.privmod_vis;
 
-        #[cfg(not(feature = "nightly_derive"))]
-        let must_use = quote!(#[must_use =
-                "Must set return value when not using the \"nightly\" feature"
-            ]);
-        #[cfg(feature = "nightly_derive")]
-        let must_use = quote!();
-
         quote!(
+            ::mockall::lazy_static! {
+                #[doc(hidden)]
+                #v static ref CONTEXTS: ::mockall::ContextTracker =
+                    ::mockall::ContextTracker::default();
+            }
+
             /// Manages the context for expectations of static methods.
             ///
             /// Expectations on this method will be validated and cleared when
-            /// the `Context` object drops.  The `Context` object does *not*
-            /// provide any form of synchronization, so multiple tests that set
-            /// expectations on the same static method must provide their own.
+            /// the `Context` object drops.  Only one `Context` for a given
+            /// method may exist at a time.  Creating a second one will panic,
+            /// unless it's created with the `_context_locked` function, which
+            /// waits for the first to drop.  Locked `Context`s of all of the
+            /// mock's static methods share one lock, which the thread holding
+            /// it may take again.
+            ///
+            /// A thread-local `Context`, created with the
+            /// `_context_thread_local` function, is independent of the others.
+            /// Its expectations apply only to calls from the thread that
+            /// created it, and from threads that attach its
+            /// [`ThreadHandle`](struct.ThreadHandle.html).
             #[must_use = "Context only serves to create expectations" ]
             #v struct Context #ty_ig #ty_wc {
+                /// The expectations that this `Context` manages
+                storage: Storage,
+                /// The slot of the thread that created this `Context`, if it's
+                /// a thread-local one
+                local: Option<LocalSlot>,
                 // Prevent "unused type parameter" errors
                 // Surprisingly, PhantomData<Fn(generics)> is Send even if
                 // generics are not, unlike PhantomData<generics>
@@ -1464,40 +2733,154 @@ impl<'a> ToTokens for Context<'a> {
                 >
             }
             impl #ty_ig Context #ty_tg #ty_wc {
+                /// Return every call made to this method since the last
+                /// checkpoint, oldest first.
+                #v fn calls #call_ig (&self) -> Vec<::mockall::Call> #call_wc
+                {
+                    ::mockall::lock(&self.storage).calls #calls_tbf ()
+                }
+
                 /// Verify that all current expectations for this method are
-                /// satisfied and clear them.
+                /// satisfied and clear them, along with the call history.
                 #v fn checkpoint(&self) {
-                    Self::do_checkpoint()
+                    self.do_checkpoint()
+                }
+                /// Check that every current expectation for this method has
+                /// been called an allowed number of times, without panicking
+                /// and without clearing anything.
+                #v fn verify(&self)
+                    -> std::result::Result<(), Vec<::mockall::Error>>
+                {
+                    let __mockall_errors = ::mockall::lock(&self.storage)
+                        .verify();
+                    if __mockall_errors.is_empty() {
+                        Ok(())
+                    } else {
+                        Err(__mockall_errors)
+                    }
                 }
+                /// Register a new `Context`.  If `mock_lock` is given, first
+                /// take it and wait for any other `Context` to drop.
                 #[doc(hidden)]
-                #v fn do_checkpoint() {
-                    let __mockall_timeses = EXPECTATIONS
-                        .lock()
-                        .unwrap()
+                #v fn new(
+                    mock_lock: Option<&'static ::mockall::ContextLock>
+                ) -> Self {
+                    CONTEXTS.acquire(#funcname, #locked_fn, mock_lock);
+                    Context {
+                        storage: EXPECTATIONS.clone(),
+                        local: None,
+                        _phantom: std::marker::PhantomData
+                    }
+                }
+                /// Create a new thread-local `Context`.
+                #[doc(hidden)]
+                #v fn new_thread_local() -> Self {
+                    let __mockall_storage = Storage::default();
+                    let __mockall_slot = LOCAL_EXPECTATIONS.with(
+                        |__mockall_l| __mockall_l.clone()
+                    );
+                    {
+                        let mut __mockall_l = ::mockall::lock(&__mockall_slot);
+                        if __mockall_l.is_some() {
+                            drop(__mockall_l);
+                            panic!("{}: A thread-local Context for this method already exists on this thread",
+                                #funcname);
+                        }
+                        *__mockall_l = Some(__mockall_storage.clone());
+                    }
+                    Context {
+                        storage: __mockall_storage,
+                        local: Some(__mockall_slot),
+                        _phantom: std::marker::PhantomData
+                    }
+                }
+                fn do_checkpoint(&self) {
+                    // Release the lock before re-raising any violations, so
+                    // it won't be poisoned.
+                    let __mockall_violations = ::mockall::lock(&self.storage)
+                        .violations();
+                    __mockall_violations.reraise();
+                    let __mockall_timeses = ::mockall::lock(&self.storage)
                         .checkpoint()
                         .collect::<Vec<_>>();
                 }
 
                 /// Create a new expectation for this method.
-                #must_use
-                #v fn expect #meth_ig ( &self,) -> ExpectationGuard #e_tg
+                #[track_caller]
+                #v fn expect #meth_ig (&'__mockall_lt self,)
+                    -> ExpectationGuard #e_tg
                     #meth_wc
                 {
-                    ExpectationGuard::new(EXPECTATIONS.lock().unwrap())
+                    ExpectationGuard::new(::mockall::lock(&self.storage))
+                }
+
+                /// Return a handle that lets other threads, such as an async
+                /// runtime's workers, share this `Context`'s expectations.
+                /// Only useful for thread-local `Context`s.
+                #v fn thread_handle(&self) -> ThreadHandle {
+                    ThreadHandle(self.storage.clone())
                 }
             }
             impl #ty_ig Default for Context #ty_tg #ty_wc {
                 fn default() -> Self {
-                    Context {_phantom: std::marker::PhantomData}
+                    Self::new(None)
                 }
             }
             impl #ty_ig Drop for Context #ty_tg #ty_wc {
                 fn drop(&mut self) {
-                    if !std::thread::panicking() {
-                        Self::do_checkpoint()
+                    // Clear the expectations even while panicking, so they
+                    // can't affect later tests.  Nothing gets verified then.
+                    if let Some(__mockall_slot) = &self.local {
+                        {
+                            let mut __mockall_l =
+                                ::mockall::lock(__mockall_slot);
+                            if let Some(__mockall_s) = &*__mockall_l {
+                                if ::std::sync::Arc::ptr_eq(__mockall_s,
+                                                            &self.storage)
+                                {
+                                    *__mockall_l = None;
+                                }
+                            }
+                        }
+                        self.do_checkpoint();
+                    } else {
+                        CONTEXTS.release_after(|| self.do_checkpoint())
                     }
                 }
             }
+
+            /// A handle to a thread-local [`Context`](struct.Context.html)'s
+            /// expectations.
+            #[derive(Clone)]
+            #v struct ThreadHandle(Storage);
+
+            impl ThreadHandle {
+                /// Make calls to this method from the current thread use the
+                /// expectations of the `Context` that created this handle,
+                /// until the returned guard drops.
+                #v fn attach(&self) -> ThreadAttachment {
+                    let __mockall_slot = LOCAL_EXPECTATIONS.with(
+                        |__mockall_l| __mockall_l.clone()
+                    );
+                    let __mockall_prev = ::mockall::lock(&__mockall_slot)
+                        .replace(self.0.clone());
+                    ThreadAttachment(__mockall_slot, __mockall_prev)
+                }
+            }
+
+            /// Keeps the current thread attached to a
+            /// [`ThreadHandle`](struct.ThreadHandle.html)'s expectations.
+            /// Dropping it restores whatever the thread used before, so a
+            /// reused thread, like one from a pool, won't keep using the
+            /// expectations of a `Context` that has already dropped.
+            #[must_use = "the thread is detached as soon as this is dropped"]
+            #v struct ThreadAttachment(LocalSlot, Option<Storage>);
+
+            impl Drop for ThreadAttachment {
+                fn drop(&mut self) {
+                    *::mockall::lock(&self.0) = self.1.take();
+                }
+            }
         ).to_tokens(tokens);
     }
 }
@@ -1526,6 +2909,24 @@ impl<'a> ToTokens for Matcher<'a> {
                 syn::Index::from(i)
             }).collect::<Vec<_>>();
         let lg = lifetimes_to_generics(&self.f.alifetimes);
+        let pred_explanations = argnames.iter().enumerate()
+            .map(|(i, argname)| {
+                let idx = syn::Index::from(i);
+                let argname_str = quote!(#argname).to_string();
+                quote!(
+                    if let Some(__mockall_case) =
+                        __mockall_pred.#idx.find_case(false, #argname)
+                    {
+                        let __mockall_tree = std::format!("{}",
+                            __mockall_case.tree())
+                            .trim_end()
+                            .replace('\n', "\n            ");
+                        __mockall_why.push_str(&std::format!(
+                            "\n        {}: {}", #argname_str,
+                            __mockall_tree));
+                    }
+                )
+            }).collect::<TokenStream>();
         let pred_matches = argnames.iter().enumerate()
             .map(|(i, argname)| {
                 let idx = syn::Index::from(i);
@@ -1564,6 +2965,23 @@ impl<'a> ToTokens for Matcher<'a> {
                         _ => unreachable!()
                     }
                 }
+
+                /// Explain why the matcher rejected a call
+                #[allow(clippy::ptr_arg)]
+                fn explain #lg (&self, #( #argnames: &#predty, )*) -> String {
+                    match self {
+                        Matcher::Always => String::new(),
+                        Matcher::Func(_) | Matcher::FuncSt(_) =>
+                            String::from("the matching function returned false"),
+                        Matcher::Pred(__mockall_pred) => {
+                            let mut __mockall_why = String::from(
+                                "some arguments failed their predicates");
+                            #pred_explanations
+                            __mockall_why
+                        },
+                        _ => unreachable!()
+                    }
+                }
             }
 
             impl #ig Default for Matcher #tg #wc {
@@ -1599,14 +3017,17 @@ struct RefRfunc<'a> {
 
 impl<'a> ToTokens for RefRfunc<'a> {
     fn to_tokens(&self, tokens: &mut TokenStream) {
+        let argnames = &self.f.argnames;
+        let argty = &self.f.argty;
         let fn_params = &self.f.fn_params;
+        let hrtb = self.f.hrtb();
         let (ig, tg, wc) = self.f.egenerics.split_for_impl();
         let lg = lifetimes_to_generics(&self.f.alifetimes);
         let owned_output = &self.f.owned_output;
 
         #[cfg(not(feature = "nightly_derive"))]
         let default_err_msg =
-            "Returning default values requires the \"nightly\" feature";
+            "Can only return default values for types that impl std::Default, and for generic types only with the \"nightly\" feature";
         #[cfg(feature = "nightly_derive")]
         let default_err_msg =
             "Can only return default values for types that impl std::Default";
@@ -1615,6 +3036,16 @@ impl<'a> ToTokens for RefRfunc<'a> {
             enum Rfunc #ig #wc {
                 Default(Option<#owned_output>),
                 Const(#owned_output),
+                // The values that the closure returns are kept in the
+                // RefStore, so their references remain valid.
+                Mut(Mutex<Box<dyn #hrtb FnMut(#(#argty, )*) -> #owned_output + Send>>,
+                    ::mockall::RefStore<#owned_output>),
+                // Version of Rfunc::Mut for closures that aren't Send
+                MutSt(Mutex<::mockall::Fragile<
+                        Box<dyn #hrtb FnMut(#(#argty, )*) -> #owned_output>>>,
+                    ::mockall::RefStore<#owned_output>),
+                // Returns scripted values, from `return_sequence`
+                Sequence(::mockall::RefScript<#owned_output>),
                 // Prevent "unused type parameter" errors Surprisingly,
                 // PhantomData<Fn(generics)> is Send even if generics are not,
                 // unlike PhantomData<generics>
@@ -1622,7 +3053,7 @@ impl<'a> ToTokens for RefRfunc<'a> {
             }
 
             impl #ig  Rfunc #tg #wc {
-                fn call #lg (&self)
+                fn call #lg (&self, #(#argnames: #argty, )*)
                     -> std::result::Result<&#owned_output, &'static str>
                 {
                     match self {
@@ -1635,6 +3066,18 @@ impl<'a> ToTokens for RefRfunc<'a> {
                         Rfunc::Const(ref __mockall_o) => {
                             Ok(__mockall_o)
                         },
+                        Rfunc::Mut(__mockall_f, __mockall_store) => {
+                            let __mockall_o = (__mockall_f.lock().unwrap())(
+                                #(#argnames, )*);
+                            Ok(__mockall_store.store(__mockall_o))
+                        },
+                        Rfunc::MutSt(__mockall_f, __mockall_store) => {
+                            let __mockall_o =
+                                (__mockall_f.lock().unwrap().get_mut())(
+                                    #(#argnames, )*);
+                            Ok(__mockall_store.store(__mockall_o))
+                        },
+                        Rfunc::Sequence(ref __mockall_s) => __mockall_s.next(),
                         Rfunc::_Phantom(_) => unreachable!()
                     }
                 }
@@ -1643,9 +3086,10 @@ impl<'a> ToTokens for RefRfunc<'a> {
             impl #ig std::default::Default for Rfunc #tg #wc
             {
                 fn default() -> Self {
-                    use ::mockall::ReturnDefault;
-                    Rfunc::Default(::mockall::DefaultReturner::<#owned_output>
-                                ::maybe_return_default())
+                    use ::mockall::{ReturnDefaultFallback, ReturnDefaultViaDefault};
+                    Rfunc::Default(
+                        (&&::mockall::DefaultReturner::<#owned_output>::new())
+                            .maybe_return_default())
                 }
             }
         ).to_tokens(tokens);
@@ -1661,6 +3105,7 @@ impl<'a> ToTokens for RefMutRfunc<'a> {
         let argnames = &self.f.argnames;
         let argty = &self.f.argty;
         let fn_params = &self.f.fn_params;
+        let funcname = self.f.funcname();
         let (ig, tg, wc) = self.f.egenerics.split_for_impl();
         let lg = lifetimes_to_generics(&self.f.alifetimes);
         let owned_output = &self.f.owned_output;
@@ -1668,7 +3113,7 @@ impl<'a> ToTokens for RefMutRfunc<'a> {
 
         #[cfg(not(feature = "nightly_derive"))]
         let default_err_msg =
-            "Returning default values requires the \"nightly\" feature";
+            "Can only return default values for types that impl std::Default, and for generic types only with the \"nightly\" feature";
         #[cfg(feature = "nightly_derive")]
         let default_err_msg =
             "Can only return default values for types that impl std::Default";
@@ -1685,6 +3130,10 @@ impl<'a> ToTokens for RefMutRfunc<'a> {
                        ), Option<#owned_output>
                 ),
                 Var(#owned_output),
+                // One variable for each key returned by the closure
+                Keyed(Box<dyn FnMut(#(#argty, )*) -> Box<dyn ::std::any::Any>
+                          + Send + Sync>,
+                      ::mockall::VarMap<#owned_output>),
                 // Prevent "unused type parameter" errors Surprisingly,
                 // PhantomData<Fn(generics)> is Send even if generics are not,
                 // unlike PhantomData<generics>
@@ -1725,17 +3174,35 @@ impl<'a> ToTokens for RefMutRfunc<'a> {
                         Rfunc::Var(ref mut __mockall_o) => {
                             Ok(__mockall_o)
                         },
+                        Rfunc::Keyed(ref mut __mockall_f,
+                                     ref mut __mockall_vars) =>
+                        {
+                            Ok(__mockall_vars.entry(
+                                    __mockall_f(#(#argnames, )*)))
+                        },
                         Rfunc::_Phantom(_) => unreachable!()
                     }
                 }
+
+                /// Return the variable for `key`, if it has been created.
+                fn var<MockallK: 'static>(&self, key: &MockallK)
+                    -> Option<&#owned_output>
+                {
+                    match self {
+                        Rfunc::Keyed(_, ref __mockall_vars) =>
+                            __mockall_vars.get(#funcname, key),
+                        _ => None
+                    }
+                }
             }
 
             impl #ig std::default::Default for Rfunc #tg #wc
             {
                 fn default() -> Self {
-                    use ::mockall::ReturnDefault;
-                    Rfunc::Default(::mockall::DefaultReturner::<#owned_output>
-                                ::maybe_return_default())
+                    use ::mockall::{ReturnDefaultFallback, ReturnDefaultViaDefault};
+                    Rfunc::Default(
+                        (&&::mockall::DefaultReturner::<#owned_output>::new())
+                            .maybe_return_default())
                 }
             }
         ).to_tokens(tokens);
@@ -1755,6 +3222,20 @@ impl<'a> ToTokens for StaticRfunc<'a> {
         let hrtb = self.f.hrtb();
         let lg = lifetimes_to_generics(&self.f.alifetimes);
         let output = &self.f.output;
+        // A borrowed output can't be scripted, because its lifetime must come
+        // from the arguments.
+        let (script_variant, script_arm) = if self.f.return_borrowed {
+            (None, None)
+        } else {
+            (Some(quote!(
+                // Returns scripted values, from `return_sequence` or
+                // `returning_iter`
+                Script(Box<dyn FnMut()
+                    -> std::result::Result<#output, &'static str> + Send>),
+            )), Some(quote!(
+                Rfunc::Script(__mockall_f) => __mockall_f(),
+            )))
+        };
         quote!(
             #[allow(clippy::unused_unit)]
             enum Rfunc #ig #wc {
@@ -1772,6 +3253,7 @@ impl<'a> ToTokens for StaticRfunc<'a> {
                 OnceSt(::mockall::Fragile<
                     Box<dyn #hrtb FnOnce(#(#argty, )*) -> #output>>
                 ),
+                #script_variant
                 // Prevent "unused type parameter" errors Surprisingly,
                 // PhantomData<Fn(generics)> is Send even if generics are not,
                 // unlike PhantomData<generics>
@@ -1784,9 +3266,10 @@ impl<'a> ToTokens for StaticRfunc<'a> {
                 {
                     match self {
                         Rfunc::Default => {
-                            use ::mockall::ReturnDefault;
-                            ::mockall::DefaultReturner::<#output>
-                                ::return_default()
+                            use ::mockall::{ReturnDefaultFallback,
+                                            ReturnDefaultViaDefault};
+                            (&&::mockall::DefaultReturner::<#output>::new())
+                                .return_default()
                         },
                         Rfunc::Expired => {
                             Err("called twice, but it returns by move")
@@ -1813,6 +3296,7 @@ impl<'a> ToTokens for StaticRfunc<'a> {
                                 unreachable!()
                             }
                         },
+                        #script_arm
                         Rfunc::_Phantom(_) => unreachable!()
                     }
                 }
@@ -1835,11 +3319,17 @@ struct RefExpectation<'a> {
 
 impl<'a> ToTokens for RefExpectation<'a> {
     fn to_tokens(&self, tokens: &mut TokenStream) {
+        let predexprs = &self.f.predexprs;
+        let out_names = self.f.out_names();
+        let cb_names = self.f.callbacks().into_iter()
+            .flatten()
+            .map(|(ident, _)| ident)
+            .collect::<Vec<_>>();
         let argnames = &self.f.argnames;
         let argty = &self.f.argty;
         let common_methods = CommonExpectationMethods{f: self.f};
         let desc = self.f.desc();
-        let funcname = self.f.funcname();
+        let hrtb = self.f.hrtb();
         let (ig, tg, wc) = self.f.egenerics.split_for_impl();
 
         let (_, common_tg, _) = self.f.cgenerics.split_for_impl();
@@ -1862,22 +3352,81 @@ impl<'a> ToTokens for RefExpectation<'a> {
                 #v fn call #lg (&self, #(#argnames: #argty, )*) -> #output
                 {
                     self.common.call(&#desc);
-                    self.rfunc.call().unwrap_or_else(|m| {
-                        let desc = std::format!(
-                            "{}", self.common.matcher.lock().unwrap());
-                        panic!("{}: Expectation({}) {}", #funcname, desc,
-                            m);
-                    })
+                    self.common.store_captures(#(#predexprs, )*);
+                    self.common.write_args(#(&mut *#out_names, )*);
+                    #(let mut #cb_names = #cb_names;)*
+                    self.common.invoke_callbacks(#(&mut #cb_names, )*);
+                    self.rfunc.call(#(#argnames, )*)
+                        .unwrap_or_else(|m| self.common.fail(m))
                 }
 
                 /// Return a reference to a constant value from the `Expectation`
                 #v fn return_const(&mut self, __mockall_o: #owned_output)
                     -> &mut Self
                 {
+                    self.common.unscript();
                     self.rfunc = Rfunc::Const(__mockall_o);
                     self
                 }
 
+                /// Supply a closure that the `Expectation` will use to create
+                /// its return values.  They will be returned by reference.
+                ///
+                /// The `Expectation` keeps every value that the closure
+                /// creates until it is dropped, so earlier references remain
+                /// valid.
+                #v fn returning<MockallF>(&mut self, __mockall_f: MockallF)
+                    -> &mut Self
+                    where MockallF: #hrtb FnMut(#(#argty, )*)
+                                    -> #owned_output + Send + 'static
+                {
+                    self.common.unscript();
+                    self.rfunc = Rfunc::Mut(Mutex::new(Box::new(__mockall_f)),
+                                            ::mockall::RefStore::default());
+                    self
+                }
+
+                /// Single-threaded version of [`returning`](#method.returning).
+                /// Can be used when the argument or return type isn't `Send`.
+                ///
+                /// It is a runtime error to call the mock method from a
+                /// different thread than the one that originally called this
+                /// method.
+                #v fn returning_st<MockallF>(&mut self, __mockall_f: MockallF)
+                    -> &mut Self
+                    where MockallF: #hrtb FnMut(#(#argty, )*)
+                                    -> #owned_output + 'static
+                {
+                    self.common.unscript();
+                    self.rfunc = Rfunc::MutSt(
+                        Mutex::new(::mockall::Fragile::new(Box::new(__mockall_f))),
+                        ::mockall::RefStore::default());
+                    self
+                }
+
+                /// Return a reference to each of `values` in turn, one per
+                /// call.  What happens afterwards is set by
+                /// [`when_exhausted`](#method.when_exhausted).
+                #v fn return_sequence(&mut self,
+                    __mockall_values: Vec<#owned_output>) -> &mut Self
+                {
+                    self.rfunc = Rfunc::Sequence(::mockall::RefScript::new(
+                        __mockall_values, self.common.script.clone()));
+                    self
+                }
+
+                /// Choose what happens once a
+                /// [`return_sequence`](#method.return_sequence) script runs
+                /// out of values.  The default is [`Exhausted::Panic`].
+                ///
+                /// [`Exhausted::Panic`]: ::mockall::Exhausted::Panic
+                #v fn when_exhausted(&mut self,
+                    __mockall_policy: ::mockall::Exhausted) -> &mut Self
+                {
+                    self.common.script.set_policy(__mockall_policy);
+                    self
+                }
+
                 #common_methods
             }
             impl #ig Default for Expectation #tg #wc
@@ -1900,11 +3449,16 @@ struct RefMutExpectation<'a> {
 
 impl<'a> ToTokens for RefMutExpectation<'a> {
     fn to_tokens(&self, tokens: &mut TokenStream) {
+        let predexprs = &self.f.predexprs;
+        let out_names = self.f.out_names();
+        let cb_names = self.f.callbacks().into_iter()
+            .flatten()
+            .map(|(ident, _)| ident)
+            .collect::<Vec<_>>();
         let common_methods = CommonExpectationMethods{f: self.f};
         let argnames = &self.f.argnames;
         let argty = &self.f.argty;
         let desc = self.f.desc();
-        let funcname = self.f.funcname();
         let (ig, tg, wc) = self.f.egenerics.split_for_impl();
         let (_, common_tg, _) = self.f.cgenerics.split_for_impl();
         let lg = lifetimes_to_generics(&self.f.alifetimes);
@@ -1926,12 +3480,13 @@ impl<'a> ToTokens for RefMutExpectation<'a> {
                     -> &mut #owned_output
                 {
                     self.common.call(&#desc);
-                    let desc = std::format!(
-                        "{}", self.common.matcher.lock().unwrap());
-                    self.rfunc.call_mut(#(#argnames, )*).unwrap_or_else(|m| {
-                            panic!("{}: Expectation({}) {}", #funcname, desc,
-                                   m);
-                    })
+                    self.common.store_captures(#(#predexprs, )*);
+                    self.common.write_args(#(&mut *#out_names, )*);
+                    #(let mut #cb_names = #cb_names;)*
+                    self.common.invoke_callbacks(#(&mut #cb_names, )*);
+                    let __mockall_common = &self.common;
+                    self.rfunc.call_mut(#(#argnames, )*)
+                        .unwrap_or_else(|m| __mockall_common.fail(m))
                 }
 
                 /// Convenience method that can be used to supply a return value
@@ -1939,10 +3494,35 @@ impl<'a> ToTokens for RefMutExpectation<'a> {
                 /// reference.
                 #v fn return_var(&mut self, __mockall_o: #owned_output) -> &mut Self
                 {
+                    self.common.unscript();
                     self.rfunc = Rfunc::Var(__mockall_o);
                     self
                 }
 
+                /// Return a different variable for each key that `key_fn`
+                /// computes from the arguments.  Each variable is created by
+                /// `init` the first time its key is seen, and is then returned
+                /// by mutable reference for every call with that key.  The
+                /// variables can be examined afterwards with
+                /// [`var`](#method.var).
+                #v fn return_var_keyed<MockallK, MockallKF, MockallI>(
+                    &mut self,
+                    mut key_fn: MockallKF,
+                    init: MockallI) -> &mut Self
+                    where MockallK: Eq + ::std::hash::Hash + Send + Sync + 'static,
+                          MockallKF: FnMut(#(#argty, )*) -> MockallK + Send + Sync + 'static,
+                          MockallI: FnMut(&MockallK) -> #owned_output + Send + Sync + 'static
+                {
+                    self.common.unscript();
+                    self.rfunc = Rfunc::Keyed(
+                        Box::new(move |#(#argnames: #argty, )*|
+                            Box::new(key_fn(#(#argnames, )*))
+                                as Box<dyn ::std::any::Any>
+                        ),
+                        ::mockall::VarMap::new(init));
+                    self
+                }
+
                 /// Supply a closure that the `Expectation` will use to create its
                 /// return value.  The return value will be returned by mutable
                 /// reference.
@@ -1950,6 +3530,7 @@ impl<'a> ToTokens for RefMutExpectation<'a> {
                     -> &mut Self
                     where MockallF: FnMut(#(#argty, )*) -> #owned_output + Send + Sync + 'static
                 {
+                    self.common.unscript();
                     self.rfunc = Rfunc::Mut(Box::new(__mockall_f), None);
                     self
                 }
@@ -1960,11 +3541,22 @@ impl<'a> ToTokens for RefMutExpectation<'a> {
                     -> &mut Self
                     where MockallF: FnMut(#(#argty, )*) -> #owned_output + 'static
                 {
+                    self.common.unscript();
                     self.rfunc = Rfunc::MutSt(
                         ::mockall::Fragile::new(Box::new(__mockall_f)), None);
                     self
                 }
 
+                /// Return the variable that
+                /// [`return_var_keyed`](#method.return_var_keyed) created for
+                /// `key`, or `None` if there is no such variable.  Panics if
+                /// `key` is not the type of its keys.
+                #v fn var<MockallK: 'static>(&self, key: &MockallK)
+                    -> Option<&#owned_output>
+                {
+                    self.rfunc.var(key)
+                }
+
                 #common_methods
             }
             impl #ig Default for Expectation #tg #wc
@@ -1987,42 +3579,28 @@ struct StaticExpectation<'a> {
 
 impl<'a> ToTokens for StaticExpectation<'a> {
     fn to_tokens(&self, tokens: &mut TokenStream) {
+        let predexprs = &self.f.predexprs;
+        let out_names = self.f.out_names();
+        let cb_names = self.f.callbacks().into_iter()
+            .flatten()
+            .map(|(ident, _)| ident)
+            .collect::<Vec<_>>();
         let common_methods = CommonExpectationMethods{f: self.f};
         let argnames = &self.f.argnames;
         let argty = &self.f.argty;
         let desc = self.f.desc();
         let hrtb = self.f.hrtb();
-        let funcname = self.f.funcname();
         let (ig, tg, wc) = self.f.egenerics.split_for_impl();
         let (_, common_tg, _) = self.f.cgenerics.split_for_impl();
         let lg = lifetimes_to_generics(&self.f.alifetimes);
         let output = &self.f.output;
         let v = &self.f.privmod_vis;
-
-        quote!(
-            /// Expectation type for methods that return a `'static` type.
-            /// This is the type returned by the `expect_*` methods.
-            #v struct Expectation #ig #wc {
-                common: Common #common_tg,
-                rfunc: Mutex<Rfunc #tg>,
-            }
-
-            #[allow(clippy::unused_unit)]
-            impl #ig Expectation #tg #wc {
-                /// Call this [`Expectation`] as if it were the real method.
-                #[doc(hidden)]
-                #v fn call #lg (&self, #(#argnames: #argty, )* ) -> #output
-                {
-                    self.common.call(&#desc);
-                    self.rfunc.lock().unwrap().call_mut(#(#argnames, )*)
-                        .unwrap_or_else(|message| {
-                            let desc = std::format!(
-                                "{}", self.common.matcher.lock().unwrap());
-                            panic!("{}: Expectation({}) {}", #funcname, desc,
-                                   message);
-                        })
-                }
-
+        // Borrowed outputs can't be stored outside of the closures that are
+        // generic over their lifetimes.
+        let consts = if self.f.return_borrowed {
+            None
+        } else {
+            Some(quote!(
                 /// Return a constant value from the `Expectation`
                 ///
                 /// The output type must be `Clone`.  The compiler can't always
@@ -2064,6 +3642,92 @@ impl<'a> ToTokens for StaticExpectation<'a> {
                 {
                     self.returning_st(move |#(#argnames, )*| __mockall_c.clone().into())
                 }
+            ))
+        };
+        let scripts = if self.f.return_borrowed {
+            None
+        } else {
+            Some(quote!(
+                /// Return each of `values` in turn, one per call.  What
+                /// happens afterwards is set by
+                /// [`when_exhausted`](#method.when_exhausted).
+                ///
+                /// Unlike [`return_const`](#method.return_const), the values
+                /// need not be `Clone`.
+                // We must use Is<#output> instead of #output because where
+                // clauses don't accept equality constraints.
+                #v fn return_sequence<MockallO>(&mut self,
+                    __mockall_values: Vec<MockallO>) -> &mut Self
+                    where MockallO: ::mockall::Is<#output> + Send + 'static,
+                          #output: 'static
+                {
+                    self.returning_iter(__mockall_values)
+                }
+
+                /// Like [`return_sequence`](#method.return_sequence), but
+                /// take the values from an iterator, which is only advanced
+                /// as the method is called.
+                #v fn returning_iter<MockallI>(&mut self,
+                    __mockall_iter: MockallI) -> &mut Self
+                    where MockallI: IntoIterator,
+                          MockallI::IntoIter: Send + 'static,
+                          MockallI::Item: ::mockall::Is<#output> + Send + 'static,
+                          #output: 'static
+                {
+                    use ::mockall::{RepeatFallback, RepeatViaClone};
+                    let __mockall_script = ::mockall::script(
+                        Box::new(__mockall_iter.into_iter()),
+                        (&&::mockall::Repeater::<#output>::new()).repeater(),
+                        self.common.script.clone());
+                    {
+                        let mut __mockall_guard = self.rfunc.lock().unwrap();
+                        *__mockall_guard.deref_mut() =
+                            Rfunc::Script(__mockall_script);
+                    }
+                    self
+                }
+
+                /// Choose what happens once a
+                /// [`return_sequence`](#method.return_sequence) or
+                /// [`returning_iter`](#method.returning_iter) script runs out
+                /// of values.  The default is [`Exhausted::Panic`].
+                ///
+                /// [`Exhausted::Panic`]: ::mockall::Exhausted::Panic
+                #v fn when_exhausted(&mut self,
+                    __mockall_policy: ::mockall::Exhausted) -> &mut Self
+                {
+                    self.common.script.set_policy(__mockall_policy);
+                    self
+                }
+            ))
+        };
+
+        quote!(
+            /// Expectation type for methods that return a `'static` type.
+            /// This is the type returned by the `expect_*` methods.
+            #v struct Expectation #ig #wc {
+                common: Common #common_tg,
+                rfunc: Mutex<Rfunc #tg>,
+            }
+
+            #[allow(clippy::unused_unit)]
+            impl #ig Expectation #tg #wc {
+                /// Call this [`Expectation`] as if it were the real method.
+                #[doc(hidden)]
+                #v fn call #lg (&self, #(#argnames: #argty, )* ) -> #output
+                {
+                    self.common.call(&#desc);
+                    self.common.store_captures(#(#predexprs, )*);
+                    self.common.write_args(#(&mut *#out_names, )*);
+                    #(let mut #cb_names = #cb_names;)*
+                    self.common.invoke_callbacks(#(&mut #cb_names, )*);
+                    // A panicking return function, like a spy's, mustn't
+                    // break later calls.
+                    ::mockall::lock(&self.rfunc).call_mut(#(#argnames, )*)
+                        .unwrap_or_else(|message| self.common.fail(message))
+                }
+
+                #consts
 
                 /// Supply an `FnOnce` closure that will provide the return
                 /// value for this Expectation.  This is useful for return types
@@ -2074,6 +3738,7 @@ impl<'a> ToTokens for StaticExpectation<'a> {
                     where MockallF: #hrtb FnOnce(#(#argty, )*)
                                     -> #output + Send + 'static
                 {
+                    self.common.unscript();
                     {
                         let mut __mockall_guard = self.rfunc.lock().unwrap();
                         *__mockall_guard.deref_mut() =
@@ -2095,6 +3760,7 @@ impl<'a> ToTokens for StaticExpectation<'a> {
                     where MockallF: #hrtb FnOnce(#(#argty, )*)
                                     -> #output + 'static
                 {
+                    self.common.unscript();
                     {
                         let mut __mockall_guard = self.rfunc.lock().unwrap();
                         *__mockall_guard.deref_mut() = Rfunc::OnceSt(
@@ -2111,6 +3777,7 @@ impl<'a> ToTokens for StaticExpectation<'a> {
                     where MockallF: #hrtb FnMut(#(#argty, )*)
                                     -> #output + Send + 'static
                 {
+                    self.common.unscript();
                     {
                         let mut __mockall_guard = self.rfunc.lock().unwrap();
                         *__mockall_guard.deref_mut() =
@@ -2119,6 +3786,8 @@ impl<'a> ToTokens for StaticExpectation<'a> {
                     self
                 }
 
+                #scripts
+
                 /// Single-threaded version of [`returning`](#method.returning).
                 /// Can be used when the argument or return type isn't `Send`.
                 ///
@@ -2130,6 +3799,7 @@ impl<'a> ToTokens for StaticExpectation<'a> {
                     where MockallF: #hrtb FnMut(#(#argty, )*)
                                     -> #output + 'static
                 {
+                    self.common.unscript();
                     {
                         let mut __mockall_guard = self.rfunc.lock().unwrap();
                         *__mockall_guard.deref_mut() = Rfunc::MutSt(
@@ -2167,6 +3837,8 @@ impl<'a> ToTokens for RefExpectations<'a> {
         let lg = lifetimes_to_generics(&self.f.alifetimes);
         let output = &self.f.output;
         let predexprs = &self.f.predexprs;
+        let record_args = self.f.record_args();
+        let desc = self.f.desc();
         let v = &self.f.privmod_vis;
         quote!(
             #common_methods
@@ -2174,16 +3846,28 @@ impl<'a> ToTokens for RefExpectations<'a> {
                 /// Simulate calling the real method.  Every current expectation
                 /// will be checked in FIFO order and the first one with
                 /// matching arguments will be used.
+                ///
+                /// If no expectation matches, return an explanation why.
                 #v fn call #lg (&self, #(#argnames: #argty, )* )
-                    -> Option<#output>
+                    -> std::result::Result<#output, String>
                 {
-                    self.0.iter()
-                        .find(|__mockall_e|
+                    let __mockall_i = self.expectations.iter()
+                        .position(|__mockall_e|
                               __mockall_e.matches(#(#predexprs, )*) &&
-                              (!__mockall_e.is_done() || self.0.len() == 1))
-                        .map(move |__mockall_e|
-                             __mockall_e.call(#(#argnames),*)
-                        )
+                              (!__mockall_e.is_done() || self.expectations.len() == 1));
+                    self.history.record(#record_args, __mockall_i,
+                        __mockall_i.map(|__mockall_i| self.expectations[__mockall_i].desc()));
+                    match __mockall_i {
+                        Some(__mockall_i) =>
+                            Ok(self.expectations[__mockall_i].call(#(#argnames),*)),
+                        None => match &self.fallback {
+                            Some(__mockall_f)
+                                if __mockall_f.matches(#(#predexprs, )*) =>
+                                Ok(__mockall_f.call(#(#argnames),*)),
+                            _ => Err(self.no_match(#desc, #record_args,
+                                self.explain(#(#predexprs, )*)))
+                        }
+                    }
                 }
 
             }
@@ -2204,7 +3888,10 @@ impl<'a> ToTokens for RefMutExpectations<'a> {
         let (ig, tg, wc) = self.f.egenerics.split_for_impl();
         let lg = lifetimes_to_generics(&self.f.alifetimes);
         let output = &self.f.output;
+        let owned_output = &self.f.owned_output;
         let predexprs = &self.f.predexprs;
+        let record_args = self.f.record_args();
+        let desc = self.f.desc();
         let v = &self.f.privmod_vis;
         quote!(
             #common_methods
@@ -2212,19 +3899,41 @@ impl<'a> ToTokens for RefMutExpectations<'a> {
                 /// Simulate calling the real method.  Every current expectation
                 /// will be checked in FIFO order and the first one with
                 /// matching arguments will be used.
+                ///
+                /// If no expectation matches, return an explanation why.
                 #v fn call_mut #lg (&mut self, #(#argnames: #argty, )* )
-                    -> Option<#output>
+                    -> std::result::Result<#output, String>
                 {
-                    let __mockall_n = self.0.len();
-                    self.0.iter_mut()
-                        .find(|__mockall_e|
+                    let __mockall_n = self.expectations.len();
+                    let __mockall_i = self.expectations.iter()
+                        .position(|__mockall_e|
                               __mockall_e.matches(#(#predexprs, )*) &&
-                              (!__mockall_e.is_done() || __mockall_n == 1))
-                        .map(move |__mockall_e|
-                             __mockall_e.call_mut(#(#argnames, )*)
-                        )
+                              (!__mockall_e.is_done() || __mockall_n == 1));
+                    self.history.record(#record_args, __mockall_i,
+                        __mockall_i.map(|__mockall_i| self.expectations[__mockall_i].desc()));
+                    match __mockall_i {
+                        Some(__mockall_i) =>
+                            Ok(self.expectations[__mockall_i].call_mut(#(#argnames, )*)),
+                        // Don't match on self.fallback directly, or its borrow
+                        // will outlive the match.
+                        None if self.fallback.as_ref().map_or(false, |__mockall_f|
+                            __mockall_f.matches(#(#predexprs, )*)) =>
+                            Ok(self.fallback.as_mut().unwrap()
+                                .call_mut(#(#argnames, )*)),
+                        None => Err(self.no_match(#desc, #record_args,
+                            self.explain(#(#predexprs, )*)))
+                    }
                 }
 
+                /// Return the first variable that any expectation has created
+                /// for `key`.
+                #v fn var<MockallK: 'static>(&self, key: &MockallK)
+                    -> Option<&#owned_output>
+                {
+                    self.expectations.iter()
+                        .chain(self.fallback.iter())
+                        .find_map(|__mockall_e| __mockall_e.var(key))
+                }
             }
         ).to_tokens(tokens);
     }
@@ -2244,6 +3953,8 @@ impl<'a> ToTokens for StaticExpectations<'a> {
         let lg = lifetimes_to_generics(&self.f.alifetimes);
         let output = &self.f.output;
         let predexprs = &self.f.predexprs;
+        let record_args = self.f.record_args();
+        let desc = self.f.desc();
         let v = &self.f.privmod_vis;
         quote!(
             #common_methods
@@ -2251,16 +3962,28 @@ impl<'a> ToTokens for StaticExpectations<'a> {
                 /// Simulate calling the real method.  Every current expectation
                 /// will be checked in FIFO order and the first one with
                 /// matching arguments will be used.
+                ///
+                /// If no expectation matches, return an explanation why.
                 #v fn call #lg (&self, #(#argnames: #argty, )* )
-                    -> Option<#output>
+                    -> std::result::Result<#output, String>
                 {
-                    self.0.iter()
-                        .find(|__mockall_e|
+                    let __mockall_i = self.expectations.iter()
+                        .position(|__mockall_e|
                               __mockall_e.matches(#(#predexprs, )*) &&
-                              (!__mockall_e.is_done() || self.0.len() == 1))
-                        .map(move |__mockall_e|
-                             __mockall_e.call(#(#argnames, )*)
-                        )
+                              (!__mockall_e.is_done() || self.expectations.len() == 1));
+                    self.history.record(#record_args, __mockall_i,
+                        __mockall_i.map(|__mockall_i| self.expectations[__mockall_i].desc()));
+                    match __mockall_i {
+                        Some(__mockall_i) =>
+                            Ok(self.expectations[__mockall_i].call(#(#argnames, )*)),
+                        None => match &self.fallback {
+                            Some(__mockall_f)
+                                if __mockall_f.matches(#(#predexprs, )*) =>
+                                Ok(__mockall_f.call(#(#argnames, )*)),
+                            _ => Err(self.no_match(#desc, #record_args,
+                                self.explain(#(#predexprs, )*)))
+                        }
+                    }
                 }
 
             }
@@ -2290,8 +4013,19 @@ impl<'a> ToTokens for GenericExpectations<'a> {
             #[doc(hidden)]
             #[derive(Default)]
             #v struct GenericExpectations{
+                /// Calls with generic parameters that had no expectations, for
+                /// `verify`
+                errors: ::mockall::ErrorLog,
+                /// Should unmatched calls be handled by a default fallback?
+                nice: bool,
                 store: std::collections::hash_map::HashMap<::mockall::Key,
-                               Box<dyn ::mockall::AnyExpectations>>
+                               Box<dyn ::mockall::AnyExpectations>>,
+                /// Calls with generic parameters that had no expectations
+                unmatched: ::std::sync::Mutex<
+                    std::collections::hash_map::HashMap<::mockall::Key,
+                                                        ::mockall::CallHistory>
+                >,
+                violations: ::mockall::Violations
             }
             impl GenericExpectations {
                 /// Verify that all current expectations are satisfied and clear
@@ -2300,12 +4034,43 @@ impl<'a> ToTokens for GenericExpectations<'a> {
                     std::collections::hash_map::Drain<::mockall::Key,
                                Box<dyn ::mockall::AnyExpectations>>
                 {
+                    self.violations.reraise();
+                    self.errors.clear();
+                    self.unmatched.lock().unwrap().clear();
                     self.store.drain()
                 }
 
                 #v fn new() -> Self {
                     Self::default()
                 }
+
+                /// Handle unmatched calls with a default fallback, for every
+                /// set of generic parameters that has expectations.
+                #v fn nice(&mut self) {
+                    self.nice = true;
+                }
+
+                /// Return the record of violations raised by this method's
+                /// calls.
+                #v fn violations(&self) -> ::mockall::Violations {
+                    self.violations.clone()
+                }
+
+                /// Check that every current expectation has been called an
+                /// allowed number of times, and gather the violations that
+                /// calls have raised, without clearing anything.  This
+                /// applies to all sets of generic parameters!
+                #v fn verify(&self) -> Vec<::mockall::Error> {
+                    self.store.values()
+                        .flat_map(|__mockall_e| __mockall_e.verify())
+                        .chain(self.errors.errors())
+                        .collect()
+                }
+            }
+            impl Drop for GenericExpectations {
+                fn drop(&mut self) {
+                    self.violations.reraise();
+                }
             }
             #ge
         ).to_tokens(tokens);
@@ -2333,6 +4098,31 @@ impl<'a> ToTokens for StaticGenericExpectations<'a> {
         let tbf = tg.as_turbofish();
         let output = &self.f.output;
         let v = &self.f.privmod_vis;
+        let desc = self.f.desc();
+        let funcname = self.f.funcname();
+        let record_args = self.f.record_args();
+        let var = if self.f.return_refmut {
+            let owned_output = &self.f.owned_output;
+            let mut vgenerics = self.f.egenerics.clone();
+            vgenerics.params.push(parse_quote!(MockallK: 'static));
+            let (vig, _, _) = vgenerics.split_for_impl();
+            Some(quote!(
+                /// Return the first variable that any expectation for this
+                /// set of generic parameters has created for `key`.
+                #v fn var #vig (&self, key: &MockallK)
+                    -> Option<&#owned_output> #wc
+                {
+                    self.store.get(&::mockall::Key::new::#keyid())
+                        .and_then(|__mockall_e| {
+                            __mockall_e.downcast_ref::<Expectations #tg>()
+                            .unwrap()
+                            .var(key)
+                        })
+                }
+            ))
+        } else {
+            None
+        };
         let (call, get, self_, downcast) = if self.f.return_refmut {
             (format_ident!("call_mut"),
              format_ident!("get_mut"),
@@ -2345,28 +4135,88 @@ impl<'a> ToTokens for StaticGenericExpectations<'a> {
              format_ident!("downcast_ref"))
         };
         quote!(
-            impl #ig ::mockall::AnyExpectations for Expectations #tg #any_wc {}
+            impl #ig ::mockall::AnyExpectations for Expectations #tg #any_wc {
+                fn verify(&self) -> Vec<::mockall::Error> {
+                    Expectations::verify(self)
+                }
+            }
             impl GenericExpectations {
                 /// Simulating calling the real method.
                 #v fn #call #ig (#self_, #(#argnames: #argty, )* )
-                    -> Option<#output> #wc
+                    -> std::result::Result<#output, String> #wc
+                {
+                    match self.store.#get(&::mockall::Key::new::#keyid()) {
+                        Some(__mockall_e) => __mockall_e.#downcast::<Expectations #tg>()
+                            .unwrap()
+                            .#call(#(#argnames, )*),
+                        None => {
+                            self.unmatched.lock().unwrap()
+                                .entry(::mockall::Key::new::#keyid())
+                                .or_default()
+                                .record(#record_args, None, None);
+                            let __mockall_why = String::from(
+                                "\n    No expectations have been set for these generic parameters"
+                            );
+                            self.errors.push(::mockall::Error::no_match(
+                                #funcname, &#desc, #record_args,
+                                &__mockall_why));
+                            Err(__mockall_why)
+                        }
+                    }
+                }
+
+                /// Return every recorded call for this set of generic
+                /// parameters, oldest first.
+                #v fn calls #ig (&self) -> Vec<::mockall::Call> #wc
                 {
-                    self.store.#get(&::mockall::Key::new::#keyid())
+                    let __mockall_key = ::mockall::Key::new::#keyid();
+                    let mut __mockall_calls = self.store.get(&__mockall_key)
                         .map(|__mockall_e| {
-                            __mockall_e.#downcast::<Expectations #tg>()
+                            __mockall_e.downcast_ref::<Expectations #tg>()
                             .unwrap()
-                            .#call(#(#argnames, )*)
-                        }).flatten()
+                            .calls()
+                        }).unwrap_or_default();
+                    if let Some(__mockall_h) = self.unmatched.lock().unwrap()
+                        .get(&__mockall_key)
+                    {
+                        __mockall_calls.extend(__mockall_h.calls());
+                        __mockall_calls.sort_by_key(::mockall::Call::index);
+                    }
+                    __mockall_calls
                 }
 
+                #var
+
                 /// Create a new Expectation.
+                #[track_caller]
                 #v fn expect #ig (&mut self) -> &mut Expectation #tg #any_wc
                 {
+                    self.expectations #tbf().expect()
+                }
+
+                /// Return the expectations for this set of generic parameters,
+                /// creating them if necessary.
+                fn expectations #ig (&mut self) -> &mut Expectations #tg
+                    #any_wc
+                {
+                    let __mockall_nice = self.nice;
                     self.store.entry(::mockall::Key::new::#keyid())
-                        .or_insert_with(|| Box::new(Expectations #tbf::new()))
-                        .downcast_mut::<Expectations #tg>()
+                        .or_insert_with(|| {
+                            let mut __mockall_ee = Expectations #tbf::new();
+                            if __mockall_nice {
+                                __mockall_ee.nice();
+                            }
+                            Box::new(__mockall_ee)
+                        }).downcast_mut::<Expectations #tg>()
                         .unwrap()
-                        .expect()
+                }
+
+                /// Return the fallback Expectation for this set of generic
+                /// parameters.
+                #[track_caller]
+                #v fn fallback #ig (&mut self) -> &mut Expectation #tg #any_wc
+                {
+                    self.expectations #tbf().fallback()
                 }
             }
         ).to_tokens(tokens)
@@ -163,6 +163,18 @@ impl ToTokens for MockItemModule {
             /// this module are satisfied and clear them.
             pub fn checkpoint() { #cp_body }
         ).to_tokens(&mut body);
+        let has_fn = self.content.iter()
+            .any(|item| matches!(item, MockItemContent::Fn(_)));
+        if has_fn {
+            quote!(
+                ::mockall::lazy_static! {
+                    #[doc(hidden)]
+                    pub static ref __MOCKALL_CONTEXT_LOCK:
+                        ::mockall::ContextLock =
+                        ::mockall::ContextLock::default();
+                }
+            ).to_tokens(&mut body);
+        }
         let docstr = {
             if let Some(ident) = &self.orig_ident {
                 let inner = format!("Mock version of the `{}` module", ident);
@@ -91,6 +91,29 @@ impl Methods {
             .collect::<Vec<_>>()
     }
 
+    /// Return fragments of code that extend `errors` with each method's
+    /// unsatisfied expectations
+    fn verifies(&self, errors: &Ident) -> Vec<impl ToTokens> {
+        self.0.iter()
+            .filter(|meth| !meth.is_static())
+            .map(|meth| meth.verify(errors))
+            .collect::<Vec<_>>()
+    }
+
+    /// Return fragments of code that make each method of `receiver` handle
+    /// unmatched calls with a default fallback
+    fn nices(&self, receiver: &TokenStream) -> Vec<TokenStream> {
+        self.0.iter()
+            .filter(|meth| !meth.is_static())
+            .map(|meth| {
+                let name = meth.name();
+                let attrs = AttrFormatter::new(&meth.attrs)
+                    .doc(false)
+                    .format();
+                quote!(#(#attrs)* #receiver.#name.nice();)
+            }).collect::<Vec<_>>()
+    }
+
     /// Return a fragment of code to initialize struct fields during default()
     fn default_inits(&self) -> Vec<TokenStream> {
         self.0.iter()
@@ -126,6 +149,12 @@ pub(crate) struct MockItemStruct {
     auto_debug: bool,
     /// Does the original struct have a `new` method?
     has_new: bool,
+    /// Does the original struct have a `nice` method?
+    has_nice: bool,
+    /// Does the original struct have a `spy` method?
+    has_spy: bool,
+    /// Does the original struct have a `verify` method?
+    has_verify: bool,
     /// Inherent methods of the mock struct
     methods: Methods,
     /// Name of the overall module that holds all of the mock stuff
@@ -156,6 +185,33 @@ impl MockItemStruct {
         }
     }
 
+    fn nice_method(&self, substruct_nices: &[TokenStream])
+        -> impl ToTokens
+    {
+        if self.has_nice {
+            TokenStream::new()
+        } else {
+            let method_nices = self.methods.nices(&quote!(__mockall_mock));
+            quote!(
+                /// Create a new "nice" mock object with no expectations.
+                ///
+                /// Calls to a nice mock's methods that match no expectation
+                /// return a default value instead of panicking, and are still
+                /// recorded in the call history.  Each method's fallback
+                /// behavior can be customized with its `fallback_*` method.
+                ///
+                /// This method will not be generated if the real struct
+                /// already has a `nice` method.
+                pub fn nice() -> Self {
+                    let mut __mockall_mock = Self::default();
+                    #(#substruct_nices)*
+                    #(#method_nices)*
+                    __mockall_mock
+                }
+            )
+        }
+    }
+
     fn new_method(&self) -> impl ToTokens {
         if self.has_new {
             TokenStream::new()
@@ -182,6 +238,123 @@ impl MockItemStruct {
     fn phantom_fields(&self) -> Vec<TokenStream> {
         phantom_fields(&self.generics)
     }
+
+    fn verify_method(&self, substruct_verifies: &[TokenStream])
+        -> impl ToTokens
+    {
+        if self.has_verify {
+            return TokenStream::new();
+        }
+        let errors = format_ident!("__mockall_errors");
+        let method_verifies = self.methods.verifies(&errors);
+        quote!(
+            /// Check that every current expectation for every method has been
+            /// called an allowed number of times.
+            ///
+            /// Unlike `checkpoint`, this neither panics nor discards the
+            /// expectations.  Static methods are not checked; use their
+            /// `Context` objects' `verify` methods instead.
+            ///
+            /// This method will not be generated if the real struct
+            /// already has a `verify` method.
+            pub fn verify(&self)
+                -> ::std::result::Result<(), ::std::vec::Vec<::mockall::Error>>
+            {
+                let mut #errors = ::std::vec::Vec::new();
+                #(#substruct_verifies)*
+                #(#method_verifies)*
+                if #errors.is_empty() {
+                    Ok(())
+                } else {
+                    Err(#errors)
+                }
+            }
+        )
+    }
+
+    /// Generate the `spy` method, or if some trait method can't be forwarded,
+    /// a doc attribute for the mock struct explaining why there is none.
+    fn spy_method(&self) -> (TokenStream, TokenStream) {
+        let (_, tg, _) = self.generics.split_for_impl();
+        let tg = quote!(#tg).to_string();
+        // Only traits that are implemented for every instance of the struct,
+        // without any cfg attributes, can bound the real object's type.
+        let traits = unique_trait_iter(self.traits.iter())
+            .filter_map(|trait_| {
+                let args = &trait_.self_path.arguments;
+                if AttrFormatter::new(&trait_.attrs)
+                    .async_trait(false)
+                    .doc(false)
+                    .format()
+                    .is_empty() &&
+                    quote!(#args).to_string() == tg
+                {
+                    trait_.bound_path().map(|bound| (trait_, bound))
+                } else {
+                    None
+                }
+            }).collect::<Vec<_>>();
+        if self.has_spy || traits.is_empty() {
+            return (TokenStream::new(), TokenStream::new());
+        }
+        let real = format_ident!("__mockall_real");
+        let bounds = traits.iter()
+            .map(|(_, bound)| bound)
+            .collect::<Vec<_>>();
+        let mut forwards = Vec::new();
+        let mut unforwardable = Vec::new();
+        for (trait_, _) in traits.iter() {
+            let fieldname = format_ident!("{}_expectations",
+                                          trait_.ss_name());
+            let receiver = quote!(__mockall_mock.#fieldname);
+            let trait_path = &trait_.trait_path;
+            let qself = quote!(__mockall_R as #trait_path);
+            for meth in trait_.methods.iter().filter(|m| !m.is_static()) {
+                match meth.spy_forward(&receiver, &real, &qself) {
+                    Some(forward) => forwards.push(forward),
+                    None => unforwardable.push(format!("`{}::{}`",
+                        trait_path.segments.last().unwrap().ident,
+                        meth.name()))
+                }
+            }
+        }
+        if !unforwardable.is_empty() {
+            // A spy that silently didn't forward these methods would panic
+            // when they're called, so don't generate one at all.
+            let docstr = format!("This mock has no `spy` method, because it can't forward {}.",
+                unforwardable.join(", "));
+            return (TokenStream::new(), quote!(#[doc = #docstr]));
+        }
+        let method = quote!(
+            /// Create a new mock object that spies on `real`.
+            ///
+            /// Calls to trait methods that match no expectation are
+            /// forwarded to `real`, and are still recorded in the call
+            /// history.  Expectations work just like for a mock created by
+            /// `new`, so they can override `real`'s results, verify call
+            /// counts, and belong to sequences.  Static methods are not
+            /// forwarded.
+            ///
+            /// This method will not be generated if the real struct
+            /// already has a `spy` method.
+            pub fn spy<__mockall_R>(real: __mockall_R) -> Self
+                where __mockall_R: #(#bounds +)* Send + 'static
+            {
+                let #real = ::std::sync::Arc::new(
+                    ::std::sync::Mutex::new(real));
+                let __mockall_spy: ::std::sync::Arc<
+                    dyn Fn(&mut Self) + Send + Sync
+                > = ::std::sync::Arc::new(move |__mockall_mock: &mut Self| {
+                    #(#forwards)*
+                });
+                let mut __mockall_mock = Self::default();
+                __mockall_spy(&mut __mockall_mock);
+                __mockall_mock.__mockall_spy = Some(__mockall_spy);
+                __mockall_mock
+            }
+        );
+        (method, TokenStream::new())
+    }
 }
 
 impl From<MockableStruct> for MockItemStruct {
@@ -190,19 +363,23 @@ impl From<MockableStruct> for MockItemStruct {
         let modname = gen_mod_ident(&mockable.name, None);
         let generics = mockable.generics.clone();
         let struct_name = &mockable.name;
-        let vis = mockable.vis;
-        let has_new = mockable.methods.iter()
-            .any(|meth| meth.sig.ident == "new") ||
+        let has_method = |name: &str| mockable.methods.iter()
+            .any(|meth| meth.sig.ident == name) ||
             mockable.impls.iter()
             .any(|impl_|
                 impl_.items.iter()
                     .any(|ii| if let ImplItem::Method(iim) = ii {
-                            iim.sig.ident == "new"
+                            iim.sig.ident == name
                         } else {
                             false
                         }
                     )
             );
+        let has_new = has_method("new");
+        let has_nice = has_method("nice");
+        let has_spy = has_method("spy");
+        let has_verify = has_method("verify");
+        let vis = mockable.vis;
         let methods = Methods(mockable.methods.into_iter()
             .map(|meth|
                 mock_function::Builder::new(&meth.sig, &meth.vis)
@@ -224,6 +401,9 @@ impl From<MockableStruct> for MockItemStruct {
             consts: mockable.consts,
             generics,
             has_new,
+            has_nice,
+            has_spy,
+            has_verify,
             methods,
             modname,
             name: mockable.name,
@@ -254,9 +434,36 @@ impl ToTokens for MockItemStruct {
             .filter(|meth| !meth.is_static())
             .map(|meth| meth.expect(modname, None))
             .collect::<Vec<_>>();
+        let fallbacks = self.methods.0.iter()
+            .filter(|meth| !meth.is_static())
+            .map(|meth| meth.fallback(modname, None))
+            .collect::<Vec<_>>();
+        let histories = self.methods.0.iter()
+            .filter(|meth| !meth.is_static())
+            .map(|meth| meth.calls())
+            .collect::<Vec<_>>();
+        let vars = self.methods.0.iter()
+            .filter(|meth| !meth.is_static())
+            .filter_map(|meth| meth.var())
+            .collect::<Vec<_>>();
         let method_checkpoints = self.methods.checkpoints();
         let new_method = self.new_method();
         let priv_mods = self.methods.priv_mods();
+        let has_static = self.methods.0.iter()
+            .chain(self.traits.iter().flat_map(|trait_| trait_.methods.iter()))
+            .any(|meth| meth.is_static());
+        let context_lock = if has_static {
+            quote!(
+                ::mockall::lazy_static! {
+                    #[doc(hidden)]
+                    pub static ref __MOCKALL_CONTEXT_LOCK:
+                        ::mockall::ContextLock =
+                        ::mockall::ContextLock::default();
+                }
+            )
+        } else {
+            TokenStream::new()
+        };
         let substructs = unique_trait_iter(self.traits.iter())
             .map(|trait_| {
                 MockItemTraitImpl {
@@ -280,6 +487,32 @@ impl ToTokens for MockItemStruct {
                 let fieldname = &ss.fieldname;
                 quote!(#(#attrs)* self.#fieldname.checkpoint();)
             }).collect::<Vec<_>>();
+        let substruct_nices = substructs.iter()
+            .filter(|ss| !ss.all_static())
+            .map(|ss| {
+                let attrs = AttrFormatter::new(&ss.attrs)
+                    .async_trait(false)
+                    .doc(false)
+                    .format();
+                let fieldname = &ss.fieldname;
+                quote!(#(#attrs)* __mockall_mock.#fieldname.nice();)
+            }).collect::<Vec<_>>();
+        let substruct_verifies = substructs.iter()
+            .filter(|ss| !ss.all_static())
+            .map(|ss| {
+                let attrs = AttrFormatter::new(&ss.attrs)
+                    .async_trait(false)
+                    .doc(false)
+                    .format();
+                let fieldname = &ss.fieldname;
+                quote!(
+                    #(#attrs)*
+                    __mockall_errors.extend(self.#fieldname.verify());
+                )
+            }).collect::<Vec<_>>();
+        let nice_method = self.nice_method(&substruct_nices);
+        let (spy_method, spy_doc) = self.spy_method();
+        let verify_method = self.verify_method(&substruct_verifies);
         let mut field_definitions = substructs.iter()
             .filter(|ss| !ss.all_static())
             .map(|ss| {
@@ -293,6 +526,11 @@ impl ToTokens for MockItemStruct {
             }).collect::<Vec<_>>();
         field_definitions.extend(self.methods.field_definitions(modname));
         field_definitions.extend(self.phantom_fields());
+        field_definitions.push(quote!(
+            __mockall_spy: ::std::option::Option<::std::sync::Arc<
+                dyn Fn(&mut #struct_name #tg) + Send + Sync
+            >>
+        ));
         let mut default_inits = substructs.iter()
             .filter(|ss| !ss.all_static())
             .map(|ss| {
@@ -305,6 +543,7 @@ impl ToTokens for MockItemStruct {
             }).collect::<Vec<_>>();
         default_inits.extend(self.methods.default_inits());
         default_inits.extend(self.phantom_default_inits());
+        default_inits.push(quote!(__mockall_spy: None));
         let trait_impls = self.traits.iter()
             .map(|trait_| {
                 let modname = format_ident!("{}_{}", &self.modname,
@@ -317,12 +556,14 @@ impl ToTokens for MockItemStruct {
             #[allow(missing_docs)]
             pub mod #modname {
                 use super::*;
+                #context_lock
                 #(#priv_mods)*
             }
             #[allow(non_camel_case_types)]
             #[allow(non_snake_case)]
             #[allow(missing_docs)]
             #(#attrs)*
+            #spy_doc
             #vis struct #struct_name #ig #wc
             {
                 #(#field_definitions),*
@@ -342,13 +583,22 @@ impl ToTokens for MockItemStruct {
                 #(#calls)*
                 #(#contexts)*
                 #(#expects)*
+                #(#fallbacks)*
+                #(#histories)*
+                #(#vars)*
                 /// Validate that all current expectations for all methods have
                 /// been satisfied, and discard them.
                 pub fn checkpoint(&mut self) {
                     #(#substruct_expectations)*
                     #(#method_checkpoints)*
+                    if let Some(__mockall_spy) = self.__mockall_spy.clone() {
+                        __mockall_spy(self);
+                    }
                 }
                 #new_method
+                #nice_method
+                #spy_method
+                #verify_method
             }
             #(#trait_impls)*
         ).to_tokens(tokens);
@@ -392,6 +642,9 @@ impl ToTokens for MockItemTraitImpl {
         let (ig, tg, wc) = self.generics.split_for_impl();
         let modname = &self.modname;
         let method_checkpoints = self.methods.checkpoints();
+        let method_nices = self.methods.nices(&quote!(self));
+        let errors = format_ident!("__mockall_errors");
+        let method_verifies = self.methods.verifies(&errors);
         let mut default_inits = self.methods.default_inits();
         default_inits.extend(self.phantom_default_inits());
         let mut field_definitions = self.methods.field_definitions(modname);
@@ -428,6 +681,20 @@ impl ToTokens for MockItemTraitImpl {
                 pub fn checkpoint(&mut self) {
                     #(#method_checkpoints)*
                 }
+
+                /// Make every method handle unmatched calls with a default
+                /// fallback.
+                pub fn nice(&mut self) {
+                    #(#method_nices)*
+                }
+
+                /// Return every unsatisfied expectation, without panicking or
+                /// discarding them.
+                pub fn verify(&self) -> ::std::vec::Vec<::mockall::Error> {
+                    let mut #errors = ::std::vec::Vec::new();
+                    #(#method_verifies)*
+                    #errors
+                }
             }
         ).to_tokens(tokens);
     }
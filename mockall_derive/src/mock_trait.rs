@@ -13,9 +13,16 @@ use syn::{
 use crate::{
     AttrFormatter,
     mock_function::{self, MockFunction},
-    compile_error
+    compile_error,
+    is_passthrough
 };
 
+/// Does this method have a body?  Methods in `mock!` usually don't.
+fn has_body(block: &Block) -> bool {
+    // How syn parses a method without a body, like `fn foo(&self);`
+    !matches!(block.stmts.as_slice(), [Stmt::Item(Item::Verbatim(_))])
+}
+
 pub(crate) struct MockTrait {
     pub attrs: Vec<Attribute>,
     pub consts: Vec<ImplItemConst>,
@@ -27,7 +34,7 @@ pub(crate) struct MockTrait {
     pub trait_path: Path,
     /// Path on which the trait is implemented.  Usually will be the same as
     /// structname, but might include concrete generic parameters.
-    self_path: PathSegment,
+    pub self_path: PathSegment,
     pub types: Vec<ImplItemType>,
     pub unsafety: Option<Token![unsafe]>
 }
@@ -52,6 +59,35 @@ impl MockTrait {
         &self.ss_name
     }
 
+    /// The trait's path, with bindings for all of its associated types, like
+    /// `Iterator<Item = u32>`.  Returns `None` if an associated type is
+    /// generic, because such types can't be bound that way.
+    pub fn bound_path(&self) -> Option<Path> {
+        let mut path = self.trait_path.clone();
+        if self.types.is_empty() {
+            return Some(path);
+        }
+        let last = path.segments.last_mut().unwrap();
+        if last.arguments.is_empty() {
+            last.arguments = PathArguments::AngleBracketed(
+                parse2(quote!(<>)).unwrap()
+            );
+        }
+        if let PathArguments::AngleBracketed(abga) = &mut last.arguments {
+            for iit in self.types.iter() {
+                if !iit.generics.params.is_empty() {
+                    return None;
+                }
+                let ident = &iit.ident;
+                let ty = &iit.ty;
+                abga.args.push(parse2(quote!(#ident = #ty)).unwrap());
+            }
+            Some(path)
+        } else {
+            None
+        }
+    }
+
     /// Create a new MockTrait
     ///
     /// # Arguments
@@ -89,16 +125,26 @@ impl MockTrait {
                 ImplItem::Const(iic) => {
                     consts.push(iic);
                 },
-                ImplItem::Method(iim) => {
-                    let mf = mock_function::Builder::new(&iim.sig, vis)
-                        .attrs(&iim.attrs)
+                ImplItem::Method(mut iim) => {
+                    let passthrough = iim.attrs.iter().any(is_passthrough);
+                    iim.attrs.retain(|attr| !is_passthrough(attr));
+                    let mut builder = mock_function::Builder::new(&iim.sig,
+                                                                  vis);
+                    builder.attrs(&iim.attrs)
                         .levels(2)
                         .call_levels(0)
                         .struct_(structname)
                         .struct_generics(struct_generics)
-                        .trait_(&ss_name)
-                        .build();
-                    methods.push(mf);
+                        .trait_(&ss_name);
+                    if passthrough {
+                        if has_body(&iim.block) {
+                            builder.passthrough(&iim.block);
+                        } else {
+                            compile_error(iim.sig.span(),
+                                "passthrough methods must have a body");
+                        }
+                    }
+                    methods.push(builder.build());
                 },
                 ImplItem::Type(iit) => {
                     types.push(iit);
@@ -155,6 +201,23 @@ impl MockTrait {
                     meth.expect(modname, Some(path_args))
                 }
             }).collect::<Vec<_>>();
+        let fallbacks = self.methods.iter()
+            .filter(|meth| !meth.is_static())
+            .map(|meth| {
+                if meth.is_method_generic() {
+                    meth.fallback(modname, None)
+                } else {
+                    meth.fallback(modname, Some(path_args))
+                }
+            }).collect::<Vec<_>>();
+        let histories = self.methods.iter()
+            .filter(|meth| !meth.is_static())
+            .map(|meth| meth.calls())
+            .collect::<Vec<_>>();
+        let vars = self.methods.iter()
+            .filter(|meth| !meth.is_static())
+            .filter_map(|meth| meth.var())
+            .collect::<Vec<_>>();
         let trait_path = &self.trait_path;
         let self_path = &self.self_path;
         let types = &self.types;
@@ -169,6 +232,9 @@ impl MockTrait {
             #(#impl_attrs)*
             impl #ig #self_path #wc {
                 #(#expects)*
+                #(#fallbacks)*
+                #(#histories)*
+                #(#vars)*
                 #(#contexts)*
             }
         )
@@ -253,20 +253,23 @@ fn tic2iic(tic: TraitItemConst, vis: &syn::Visibility) -> ImplItemConst {
     }
 }
 
-/// Converts a TraitItemMethod into an ImplItemMethod
+/// Converts a TraitItemMethod into an ImplItemMethod, keeping its default
+/// body, if any
 fn tim2iim(m: syn::TraitItemMethod, vis: &syn::Visibility)
     -> syn::ImplItemMethod
 {
-    let empty_block = Block {
+    // Represent a missing body the same way that syn does for an
+    // ImplItemMethod like `fn foo(&self);`
+    let block = m.default.unwrap_or_else(|| Block {
         brace_token: token::Brace::default(),
-        stmts: Vec::new()
-    };
+        stmts: vec![Stmt::Item(Item::Verbatim(quote!(;)))]
+    });
     syn::ImplItemMethod{
         attrs: m.attrs,
         vis: vis.clone(),
         defaultness: None,
         sig: m.sig,
-        block: empty_block
+        block
     }
 }
 